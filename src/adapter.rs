@@ -0,0 +1,111 @@
+use std::iter;
+
+use crate::{functions::block_size, PdAudioContext};
+
+/// Adapts host buffers of any size to the fixed block size of pd.
+///
+/// Input frames are collected until they fill a pd tick and the output of the tick is handed out frame by frame,
+/// so hosts which call back with e.g. 441 or 480 frames work without dropping or repeating samples.
+/// This adds a fixed latency of one block, see [`latency`](BlockAdapter::latency).
+///
+/// The buffers are allocated once in [`new`](BlockAdapter::new), processing doesn't allocate.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+/// pd.open_patch("synth.pd").unwrap();
+/// pd.dsp_on().unwrap();
+///
+/// let mut adapter = pd.block_adapter();
+///
+/// // In the audio callback, with any amount of frames.
+/// let input = [0.0_f32; 441];
+/// let mut output = [0.0_f32; 882];
+/// adapter.process_float(&input, &mut output);
+/// ```
+#[derive(Debug)]
+pub struct BlockAdapter {
+    context: PdAudioContext,
+    input_channels: usize,
+    output_channels: usize,
+    block_size: usize,
+    /// Interleaved input of the next tick.
+    input: Vec<f32>,
+    /// Interleaved output of the last tick.
+    output: Vec<f32>,
+    /// Frames of the current tick which are exchanged with the host so far.
+    position: usize,
+}
+
+impl BlockAdapter {
+    /// Creates an adapter for the instance of the context, with the channel counts the instance is configured with.
+    pub fn new(context: PdAudioContext) -> Self {
+        let (input_channels, output_channels) = (context.input_channels, context.output_channels);
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "The block size of pd is a positive constant."
+        )]
+        let block_size = block_size() as usize;
+        Self {
+            context,
+            input_channels,
+            output_channels,
+            block_size,
+            input: vec![0.0; block_size * input_channels],
+            // The first block is silence, which is the latency of the adapter.
+            output: vec![0.0; block_size * output_channels],
+            position: 0,
+        }
+    }
+
+    /// Returns the latency the adapter adds, in frames.
+    ///
+    /// It is always one pd block, the output lags behind the input by exactly this amount of frames.
+    pub const fn latency(&self) -> usize {
+        self.block_size
+    }
+
+    /// Processes interleaved buffers of any amount of frames.
+    ///
+    /// The amount of frames is determined by the output buffer, or the input buffer if there are no output channels.
+    /// Missing input samples are treated as silence.
+    pub fn process_float(&mut self, input: &[f32], output: &mut [f32]) {
+        let frames = output
+            .len()
+            .checked_div(self.output_channels)
+            .or_else(|| input.len().checked_div(self.input_channels))
+            .unwrap_or(0);
+
+        let mut input = input.iter().copied().chain(iter::repeat(0.0));
+        let mut output = output.iter_mut();
+        let mut remaining = frames;
+        while remaining > 0 {
+            let now = remaining.min(self.block_size - self.position);
+
+            for (slot, sample) in self
+                .input
+                .iter_mut()
+                .skip(self.position * self.input_channels)
+                .zip(input.by_ref().take(now * self.input_channels))
+            {
+                *slot = sample;
+            }
+            for (sample, slot) in output.by_ref().take(now * self.output_channels).zip(
+                self.output
+                    .iter()
+                    .skip(self.position * self.output_channels),
+            ) {
+                *sample = *slot;
+            }
+
+            self.position += now;
+            remaining -= now;
+            if self.position == self.block_size {
+                self.context.process_float(1, &self.input, &mut self.output);
+                self.position = 0;
+            }
+        }
+    }
+}
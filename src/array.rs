@@ -0,0 +1,475 @@
+use std::{ffi::CString, mem, ops::Range, os::raw::c_long, path::Path, ptr, slice};
+
+use hound::{WavReader, WavWriter};
+use libpd_sys::{t_garray, t_symbol, t_word};
+
+use crate::{
+    error::{ArrayError, StringConversionError},
+    render::{read_samples, write_samples, WavSampleFormat},
+    Pd,
+};
+
+/// A handle to a named float array in the patches which are open in a [`Pd`] instance.
+///
+/// The name is converted to a pd symbol once when the handle is created,
+/// the array is found by the symbol with every operation so the handle stays valid
+/// when the patch which holds the array is closed and opened again.
+///
+/// Every operation sets the instance as the current one and locks pd while it runs.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+///
+/// let sketch_pad = pd.array("sketch_pad").unwrap();
+/// sketch_pad.resize_preserving(4).unwrap();
+/// sketch_pad.write_from(1, &[0.5, 0.25]).unwrap();
+/// assert_eq!(sketch_pad.to_vec().unwrap(), vec![0.0, 0.5, 0.25, 0.0]);
+/// ```
+pub struct PdArray<'a> {
+    pd: &'a Pd,
+    name: String,
+    symbol: *mut t_symbol,
+}
+
+impl<'a> PdArray<'a> {
+    /// Creates a handle to the array with the name, the array needs to exist.
+    pub(crate) fn new(pd: &'a Pd, name: &str) -> Result<Self, ArrayError> {
+        let c_name = CString::new(name).map_err(StringConversionError::from)?;
+        let _guard = pd.set_as_active_instance();
+        let array = Self {
+            pd,
+            name: name.to_owned(),
+            symbol: unsafe { libpd_sys::gensym(c_name.as_ptr()) },
+        };
+        array.len()?;
+        Ok(array)
+    }
+
+    /// Returns the name of the array.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the amount of elements in the array.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn len(&self) -> Result<usize, ArrayError> {
+        self.with_words(|words| words.len())
+    }
+
+    /// Checks if the array has no elements, pd never sizes an array below `1`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn is_empty(&self) -> Result<bool, ArrayError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Reads the elements in the range of the array to the destination.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    /// - [`LengthMismatch`](crate::error::ArrayError::LengthMismatch)
+    pub fn read_into(
+        &self,
+        range: Range<usize>,
+        destination: &mut [f32],
+    ) -> Result<(), ArrayError> {
+        if range.len() != destination.len() {
+            return Err(ArrayError::LengthMismatch {
+                expected: range.len(),
+                found: destination.len(),
+            });
+        }
+        self.with_words(|words| {
+            let words = words.get(range).ok_or(ArrayError::OutOfBounds)?;
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "pd arrays hold 64-bit floats, they are read as f32."
+            )]
+            for (value, word) in destination.iter_mut().zip(words) {
+                *value = unsafe { word.w_float } as f32;
+            }
+            Ok(())
+        })?
+    }
+
+    /// Writes the source to the array starting from the offset.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn write_from(&self, offset: usize, source: &[f32]) -> Result<(), ArrayError> {
+        self.with_words(|words| {
+            let words = words
+                .get_mut(offset..offset.saturating_add(source.len()))
+                .ok_or(ArrayError::OutOfBounds)?;
+            for (word, value) in words.iter_mut().zip(source) {
+                word.w_float = f64::from(*value);
+            }
+            Ok(())
+        })?
+    }
+
+    /// Reads every element of the array.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn to_vec(&self) -> Result<Vec<f32>, ArrayError> {
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "pd arrays hold 64-bit floats, they are read as f32."
+        )]
+        let values = self.with_words(|words| {
+            words
+                .iter()
+                .map(|word| unsafe { word.w_float } as f32)
+                .collect()
+        })?;
+        Ok(values)
+    }
+
+    /// Sets every element of the array to the value.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn fill(&self, value: f32) -> Result<(), ArrayError> {
+        self.with_words(|words| {
+            for word in words {
+                word.w_float = f64::from(value);
+            }
+        })
+    }
+
+    /// Resizes the array, the elements which fit in the new size keep their values and the new ones are `0`.
+    ///
+    /// Sizes of `0` or over the size limit of pd are clipped to `1`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn resize_preserving(&self, size: usize) -> Result<(), ArrayError> {
+        let size = c_long::try_from(size).map_err(|_| ArrayError::OutOfBounds)?;
+        self.with_array(|array| {
+            unsafe { libpd_sys::garray_resize_long(array, size) };
+            Ok(())
+        })
+    }
+
+    /// Resizes the array to the length of a channel of a WAV file and loads the channel to it.
+    ///
+    /// Samples are loaded as they are, use [`load_wav_resampled`](PdArray::load_wav_resampled)
+    /// to play the file at its pitch with an instance running at another sample rate.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`ChannelOutOfRange`](crate::error::ArrayError::ChannelOutOfRange)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    /// - [`Wav`](crate::error::ArrayError::Wav)
+    pub fn load_wav<P: AsRef<Path>>(&self, path: P, channel: usize) -> Result<(), ArrayError> {
+        load_wav_channels(path, &[(channel, self)])
+    }
+
+    /// Works like [`load_wav`](PdArray::load_wav) but resamples the channel to the sample rate of the instance.
+    ///
+    /// Resampling interpolates linearly between the samples of the file.
+    ///
+    /// # Errors
+    ///
+    /// See [`load_wav`](PdArray::load_wav).
+    pub fn load_wav_resampled<P: AsRef<Path>>(
+        &self,
+        path: P,
+        channel: usize,
+    ) -> Result<(), ArrayError> {
+        load_wav_channels_resampled(path, &[(channel, self)])
+    }
+
+    /// Saves the array to a mono WAV file of 32-bit float samples with the sample rate.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`Wav`](crate::error::ArrayError::Wav)
+    pub fn save_wav<P: AsRef<Path>>(&self, path: P, sample_rate: u32) -> Result<(), ArrayError> {
+        let samples = self.to_vec()?;
+        let sample_format = WavSampleFormat::Float32;
+        let mut writer = WavWriter::create(path, sample_format.spec(1, sample_rate))?;
+        write_samples(&mut writer, sample_format, &samples)?;
+        writer.finalize()?;
+        Ok(())
+    }
+
+    /// Starts watching the array for changes, see [`ArrayWatcher`].
+    ///
+    /// Changes are tracked in chunks of the size, `0` is treated as `1`.
+    pub fn watch(self, chunk_size: usize) -> ArrayWatcher<'a> {
+        ArrayWatcher {
+            array: self,
+            chunk_size: chunk_size.max(1),
+            checksums: Vec::new(),
+            next_checksums: Vec::new(),
+        }
+    }
+
+    /// Resizes the array to the length of the samples and writes them to it.
+    fn load(&self, samples: &[f32]) -> Result<(), ArrayError> {
+        self.resize_preserving(samples.len())?;
+        if samples.is_empty() {
+            // pd keeps one element of an array.
+            return self.fill(0.0);
+        }
+        self.write_from(0, samples)
+    }
+
+    /// Finds the array and calls the function with it while pd is locked.
+    fn with_array<R, F: FnOnce(*mut t_garray) -> Result<R, ArrayError>>(
+        &self,
+        function: F,
+    ) -> Result<R, ArrayError> {
+        let _guard = self.pd.set_as_active_instance();
+        unsafe { libpd_sys::sys_lock() };
+        let array = unsafe { libpd_sys::pd_findbyclass(self.symbol, libpd_sys::garray_class) }
+            .cast::<t_garray>();
+        let result = if array.is_null() {
+            Err(ArrayError::FailedToFindArray)
+        } else {
+            function(array)
+        };
+        unsafe { libpd_sys::sys_unlock() };
+        result
+    }
+
+    /// Finds the array and calls the function with its elements while pd is locked.
+    fn with_words<R, F: FnOnce(&mut [t_word]) -> R>(&self, function: F) -> Result<R, ArrayError> {
+        self.with_array(|array| {
+            let mut size = 0;
+            let mut words = ptr::null_mut();
+            // Arrays of other element types than float are not found.
+            if unsafe { libpd_sys::garray_getfloatwords(array, &mut size, &mut words) } == 0 {
+                return Err(ArrayError::FailedToFindArray);
+            }
+            #[expect(clippy::cast_sign_loss, reason = "pd never sizes an array below 1.")]
+            let words = unsafe { slice::from_raw_parts_mut(words, size as usize) };
+            Ok(function(words))
+        })
+    }
+}
+
+/// Watches a [`PdArray`] for changes by comparing checksums of its chunks each time it is polled.
+///
+/// Every poll still reads and hashes every element of the array while pd is locked,
+/// but it doesn't copy them, the user interface then copies only the ranges which changed since the last poll.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+///
+/// let mut watcher = pd.array("sketch_pad").unwrap().watch(16);
+/// let mut values = Vec::new();
+/// // Every frame of the user interface:
+/// let change = watcher.poll().unwrap();
+/// values.resize(change.len, 0.0);
+/// for range in change.dirty {
+///     watcher.array().read_into(range.clone(), &mut values[range]).unwrap();
+/// }
+/// ```
+pub struct ArrayWatcher<'a> {
+    array: PdArray<'a>,
+    chunk_size: usize,
+    checksums: Vec<u64>,
+    next_checksums: Vec<u64>,
+}
+
+/// The changes of an array since the last time it is polled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayChange {
+    /// The amount of elements in the array.
+    pub len: usize,
+    /// The ranges of elements which changed, ordered and merged when they are adjacent.
+    pub dirty: Vec<Range<usize>>,
+}
+
+impl ArrayChange {
+    /// Checks if nothing changed.
+    pub fn is_clean(&self) -> bool {
+        self.dirty.is_empty()
+    }
+}
+
+impl<'a> ArrayWatcher<'a> {
+    /// Returns the array which is watched.
+    pub const fn array(&self) -> &PdArray<'a> {
+        &self.array
+    }
+
+    /// Returns the ranges which changed since the last poll, the first poll marks the whole array as changed.
+    ///
+    /// Elements which are added by a resize are changed, a chunk could rarely be missed
+    /// if its elements change to values with the same checksum.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn poll(&mut self) -> Result<ArrayChange, ArrayError> {
+        let Self {
+            array,
+            chunk_size,
+            next_checksums,
+            ..
+        } = self;
+        let len = array.with_words(|words| {
+            next_checksums.clear();
+            next_checksums.extend(words.chunks(*chunk_size).map(checksum));
+            words.len()
+        })?;
+
+        let mut dirty: Vec<Range<usize>> = Vec::new();
+        for (index, next) in self.next_checksums.iter().enumerate() {
+            if self.checksums.get(index) == Some(next) {
+                continue;
+            }
+            let start = index * self.chunk_size;
+            let end = (start + self.chunk_size).min(len);
+            match dirty.last_mut() {
+                Some(last) if last.end == start => last.end = end,
+                _ => dirty.push(start..end),
+            }
+        }
+        mem::swap(&mut self.checksums, &mut self.next_checksums);
+        Ok(ArrayChange { len, dirty })
+    }
+}
+
+/// A 64-bit FNV-1a like hash of the bits of the elements, taken a word at a time.
+fn checksum(words: &[t_word]) -> u64 {
+    words.iter().fold(0xcbf2_9ce4_8422_2325, |hash, word| {
+        (hash ^ unsafe { word.w_float }.to_bits()).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Loads the channels of a WAV file to arrays, every channel is paired with the array it is loaded to.
+///
+/// The file is read once for all of the channels, samples are loaded as they are.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{array::load_wav_channels, Pd};
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("sampler.pd").unwrap();
+///
+/// let (left, right) = (pd.array("left").unwrap(), pd.array("right").unwrap());
+/// load_wav_channels("break.wav", &[(0, &left), (1, &right)]).unwrap();
+/// ```
+///
+/// # Errors
+///
+/// See [`PdArray::load_wav`].
+pub fn load_wav_channels<P: AsRef<Path>>(
+    path: P,
+    arrays: &[(usize, &PdArray<'_>)],
+) -> Result<(), ArrayError> {
+    let (samples, channels, _) = read_wav(path.as_ref())?;
+    for (channel, array) in arrays {
+        array.load(&channel_samples(&samples, channels, *channel)?)?;
+    }
+    Ok(())
+}
+
+/// Works like [`load_wav_channels`] but resamples the channels to the sample rate of the instance of each array.
+///
+/// # Errors
+///
+/// See [`PdArray::load_wav`].
+pub fn load_wav_channels_resampled<P: AsRef<Path>>(
+    path: P,
+    arrays: &[(usize, &PdArray<'_>)],
+) -> Result<(), ArrayError> {
+    let (samples, channels, sample_rate) = read_wav(path.as_ref())?;
+    for (channel, array) in arrays {
+        let samples = channel_samples(&samples, channels, *channel)?;
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "Pd is configured with a positive sample rate."
+        )]
+        let target = array.pd.sample_rate() as u32;
+        array.load(&resample(&samples, sample_rate, target))?;
+    }
+    Ok(())
+}
+
+/// Reads the interleaved samples, the channel count and the sample rate of a WAV file.
+fn read_wav(path: &Path) -> Result<(Vec<f32>, u16, u32), ArrayError> {
+    let reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    let samples = read_samples(reader).collect::<Result<Vec<f32>, _>>()?;
+    Ok((samples, spec.channels, spec.sample_rate))
+}
+
+fn channel_samples(samples: &[f32], channels: u16, channel: usize) -> Result<Vec<f32>, ArrayError> {
+    if channel >= usize::from(channels) {
+        return Err(ArrayError::ChannelOutOfRange { channel, channels });
+    }
+    Ok(samples
+        .iter()
+        .skip(channel)
+        .step_by(usize::from(channels))
+        .copied()
+        .collect())
+}
+
+/// Resamples by interpolating linearly between the samples.
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "Sample positions are far below the precision limit of f64 and never negative."
+)]
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || from == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let step = f64::from(from) / f64::from(to);
+    let length = (samples.len() as f64 / step).ceil() as usize;
+    let last = samples.len() - 1;
+    (0..length)
+        .map(|index| {
+            let position = index as f64 * step;
+            let before = (position.floor() as usize).min(last);
+            let after = (before + 1).min(last);
+            let fraction = (position - position.floor()) as f32;
+            let (Some(before), Some(after)) = (samples.get(before), samples.get(after)) else {
+                return 0.0;
+            };
+            before + (after - before) * fraction
+        })
+        .collect()
+}
@@ -4,6 +4,11 @@ use std::ptr;
 
 use crate::error::{InstanceError, PdError, StringConversionError};
 
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "serde")]
+pub use de::{from_atoms, AtomDeserializer};
+
 /// A type to represent a pd Atom type in Rust side.
 ///
 /// Pd has floating point numbers and symbols as primitive types.
@@ -89,6 +94,65 @@ impl Display for Atom {
     }
 }
 
+/// Atoms are serialized as plain numbers and strings, so a list of atoms looks like `[440.0, "sine"]` in JSON.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Atom {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Float(value) => serializer.serialize_f64(*value),
+            Self::Symbol(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+/// Numbers are deserialized as floats and strings as symbols.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Atom {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(AtomVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct AtomVisitor;
+
+#[cfg(feature = "serde")]
+impl serde::de::Visitor<'_> for AtomVisitor {
+    type Value = Atom;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a string")
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<Atom, E> {
+        Ok(Atom::Float(value))
+    }
+
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "Floats in pd can't hold larger integers precisely either."
+    )]
+    fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Atom, E> {
+        Ok(Atom::Float(value as f64))
+    }
+
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "Floats in pd can't hold larger integers precisely either."
+    )]
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Atom, E> {
+        Ok(Atom::Float(value as f64))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Atom, E> {
+        Ok(Atom::Symbol(value.to_owned()))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, value: String) -> Result<Atom, E> {
+        Ok(Atom::Symbol(value))
+    }
+}
+
 impl From<String> for Atom {
     fn from(s: String) -> Self {
         Self::Symbol(s)
@@ -0,0 +1,522 @@
+use serde::de::{self, value::BorrowedStrDeserializer, DeserializeSeed, Visitor};
+use serde::Deserialize;
+
+use crate::error::DeserializeError;
+use crate::Atom;
+
+/// Deserializes a list of atoms into a Rust type, e.g. the list which is received in [`Pd::on_list`](crate::Pd::on_list).
+///
+/// The whole list needs to be consumed by the value.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{atom::from_atoms, Pd};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Note {
+///     pitch: u8,
+///     velocity: f32,
+///     instrument: String,
+/// }
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.on_list(|_source, list| {
+///     if let Ok(note) = from_atoms::<Note>(list) {
+///         println!("{} {} {}", note.instrument, note.pitch, note.velocity);
+///     }
+/// })
+/// .unwrap();
+/// ```
+///
+/// # Errors
+///
+/// A list of errors that can occur:
+/// - [`DeserializeError`]
+///   - [`TrailingAtoms`](crate::error::DeserializeError::TrailingAtoms)
+///   - See [`AtomDeserializer`] for the rest.
+pub fn from_atoms<'de, T: Deserialize<'de>>(atoms: &'de [Atom]) -> Result<T, DeserializeError> {
+    let mut deserializer = AtomDeserializer::new(atoms);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// A serde [`Deserializer`](serde::Deserializer) which reads values from a list of atoms in order.
+///
+/// Atoms don't carry any structure, so the shape of the value decides how the atoms are read:
+/// - Numbers and booleans are read from a float, integers need to be whole numbers which fit in the type.
+/// - Strings are read from a symbol and characters from a symbol with a single character.
+/// - Structs and tuples read their fields one after another, without any names.
+/// - Sequences and maps consume every atom left in the list, so they need to come last.
+/// - Options are `None` only when the list ended.
+/// - Enums read the name of the variant from a symbol followed by the fields of the variant,
+///   which maps well to messages like `mode fast`.
+///
+/// # Errors
+///
+/// A list of errors that can occur while deserializing:
+/// - [`DeserializeError`]
+///   - [`EndOfList`](crate::error::DeserializeError::EndOfList)
+///   - [`ExpectedFloat`](crate::error::DeserializeError::ExpectedFloat)
+///   - [`ExpectedSymbol`](crate::error::DeserializeError::ExpectedSymbol)
+///   - [`ExpectedChar`](crate::error::DeserializeError::ExpectedChar)
+///   - [`NotAnInteger`](crate::error::DeserializeError::NotAnInteger)
+///   - [`Unsupported`](crate::error::DeserializeError::Unsupported)
+///   - [`Custom`](crate::error::DeserializeError::Custom)
+#[derive(Debug, Clone)]
+pub struct AtomDeserializer<'de> {
+    atoms: &'de [Atom],
+}
+
+impl<'de> AtomDeserializer<'de> {
+    /// Creates a deserializer which reads from the start of the list.
+    pub const fn new(atoms: &'de [Atom]) -> Self {
+        Self { atoms }
+    }
+
+    /// Returns the atoms which are not read yet.
+    pub const fn remaining(&self) -> &'de [Atom] {
+        self.atoms
+    }
+
+    /// Checks that every atom of the list is read.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`DeserializeError`]
+    ///   - [`TrailingAtoms`](crate::error::DeserializeError::TrailingAtoms)
+    pub const fn end(&self) -> Result<(), DeserializeError> {
+        if self.atoms.is_empty() {
+            Ok(())
+        } else {
+            Err(DeserializeError::TrailingAtoms(self.atoms.len()))
+        }
+    }
+
+    fn next_atom(&mut self) -> Result<&'de Atom, DeserializeError> {
+        let (atom, rest) = self
+            .atoms
+            .split_first()
+            .ok_or(DeserializeError::EndOfList)?;
+        self.atoms = rest;
+        Ok(atom)
+    }
+
+    fn float(&mut self) -> Result<f64, DeserializeError> {
+        match self.next_atom()? {
+            Atom::Float(value) => Ok(*value),
+            Atom::Symbol(symbol) => Err(DeserializeError::ExpectedFloat(symbol.clone())),
+        }
+    }
+
+    fn symbol(&mut self) -> Result<&'de str, DeserializeError> {
+        match self.next_atom()? {
+            Atom::Symbol(symbol) => Ok(symbol),
+            Atom::Float(value) => Err(DeserializeError::ExpectedSymbol(*value)),
+        }
+    }
+
+    fn integer<T: TryFrom<i128>>(&mut self) -> Result<T, DeserializeError> {
+        let value = self.float()?;
+        if !value.is_finite() || value.fract() != 0.0 {
+            return Err(DeserializeError::NotAnInteger(value));
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "Whole floats saturate to the range of i128 which is larger than the range of the integer types."
+        )]
+        let integer = value as i128;
+        T::try_from(integer).map_err(|_| DeserializeError::NotAnInteger(value))
+    }
+}
+
+impl de::Error for DeserializeError {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($method:ident, $visit:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.$visit(self.integer()?)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut AtomDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.next_atom()? {
+            Atom::Float(value) => visitor.visit_f64(*value),
+            Atom::Symbol(symbol) => visitor.visit_borrowed_str(symbol),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(self.float()? != 0.0)
+    }
+
+    deserialize_integer!(deserialize_i8, visit_i8);
+    deserialize_integer!(deserialize_i16, visit_i16);
+    deserialize_integer!(deserialize_i32, visit_i32);
+    deserialize_integer!(deserialize_i64, visit_i64);
+    deserialize_integer!(deserialize_i128, visit_i128);
+    deserialize_integer!(deserialize_u8, visit_u8);
+    deserialize_integer!(deserialize_u16, visit_u16);
+    deserialize_integer!(deserialize_u32, visit_u32);
+    deserialize_integer!(deserialize_u64, visit_u64);
+    deserialize_integer!(deserialize_u128, visit_u128);
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "The precision of f32 is asked for."
+        )]
+        let value = self.float()? as f32;
+        visitor.visit_f32(value)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f64(self.float()?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let symbol = self.symbol()?;
+        let mut chars = symbol.chars();
+        match (chars.next(), chars.next()) {
+            (Some(character), None) => visitor.visit_char(character),
+            _ => Err(DeserializeError::ExpectedChar(symbol.to_owned())),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.symbol()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(DeserializeError::Unsupported("Bytes"))
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(DeserializeError::Unsupported("Bytes"))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.atoms.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Rest { de: self })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Counted { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Counted { de: self, len })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(Rest { de: self })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Counted {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.next_atom()?;
+        visitor.visit_unit()
+    }
+}
+
+/// Reads a fixed amount of elements, for tuples and structs.
+struct Counted<'a, 'de> {
+    de: &'a mut AtomDeserializer<'de>,
+    len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Counted<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+/// Reads elements until the list ends, for sequences and maps.
+struct Rest<'a, 'de> {
+    de: &'a mut AtomDeserializer<'de>,
+}
+
+impl<'de> de::SeqAccess<'de> for Rest<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.de.atoms.is_empty() {
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Rest<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        if self.de.atoms.is_empty() {
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut AtomDeserializer<'de> {
+    type Error = DeserializeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant: BorrowedStrDeserializer<'de, DeserializeError> =
+            BorrowedStrDeserializer::new(self.symbol()?);
+        let value = seed.deserialize(variant)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut AtomDeserializer<'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Counted { de: self, len })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Counted {
+            de: self,
+            len: fields.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+
+    use super::*;
+    use crate::error::DeserializeError;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Note {
+        pitch: u8,
+        velocity: f32,
+        instrument: String,
+        tail: Option<bool>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Command {
+        Stop,
+        Gain(f64),
+        Move { x: i32, y: i32 },
+        Chord(Vec<u8>),
+    }
+
+    #[test]
+    fn struct_from_atoms() {
+        let atoms = [Atom::Float(60.0), Atom::Float(0.5), Atom::from("piano")];
+        assert_eq!(
+            from_atoms::<Note>(&atoms).unwrap(),
+            Note {
+                pitch: 60,
+                velocity: 0.5,
+                instrument: "piano".to_owned(),
+                tail: None,
+            }
+        );
+
+        let atoms = [
+            Atom::Float(60.0),
+            Atom::Float(0.5),
+            Atom::from("piano"),
+            Atom::Float(1.0),
+        ];
+        assert_eq!(from_atoms::<Note>(&atoms).unwrap().tail, Some(true));
+    }
+
+    #[test]
+    fn enum_from_atoms() {
+        let stop = [Atom::from("stop")];
+        assert_eq!(from_atoms::<Command>(&stop).unwrap(), Command::Stop);
+
+        let gain = [Atom::from("gain"), Atom::Float(0.25)];
+        assert_eq!(from_atoms::<Command>(&gain).unwrap(), Command::Gain(0.25));
+
+        let move_to = [Atom::from("move"), Atom::Float(-3.0), Atom::Float(4.0)];
+        assert_eq!(
+            from_atoms::<Command>(&move_to).unwrap(),
+            Command::Move { x: -3, y: 4 }
+        );
+
+        let chord = [
+            Atom::from("chord"),
+            Atom::Float(60.0),
+            Atom::Float(64.0),
+            Atom::Float(67.0),
+        ];
+        assert_eq!(
+            from_atoms::<Command>(&chord).unwrap(),
+            Command::Chord(vec![60, 64, 67])
+        );
+    }
+
+    #[test]
+    fn borrowed_and_untyped_values() {
+        let atoms = [Atom::from("osc"), Atom::Float(440.0)];
+        let (name, frequency): (&str, f64) = from_atoms(&atoms).unwrap();
+        assert_eq!((name, frequency), ("osc", 440.0));
+        assert_eq!(from_atoms::<Vec<Atom>>(&atoms).unwrap(), atoms.to_vec());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            from_atoms::<(u8, u8)>(&[Atom::Float(1.0)]),
+            Err(DeserializeError::EndOfList)
+        ));
+        assert!(matches!(
+            from_atoms::<u8>(&[Atom::Float(1.0), Atom::Float(2.0)]),
+            Err(DeserializeError::TrailingAtoms(1))
+        ));
+        assert!(matches!(
+            from_atoms::<u8>(&[Atom::Float(1.5)]),
+            Err(DeserializeError::NotAnInteger(_))
+        ));
+        assert!(matches!(
+            from_atoms::<u8>(&[Atom::Float(256.0)]),
+            Err(DeserializeError::NotAnInteger(_))
+        ));
+        assert!(matches!(
+            from_atoms::<f64>(&[Atom::from("a")]),
+            Err(DeserializeError::ExpectedFloat(_))
+        ));
+        assert!(matches!(
+            from_atoms::<String>(&[Atom::Float(1.0)]),
+            Err(DeserializeError::ExpectedSymbol(_))
+        ));
+        assert!(matches!(
+            from_atoms::<Command>(&[Atom::from("jump")]),
+            Err(DeserializeError::Custom(_))
+        ));
+    }
+}
@@ -48,6 +48,24 @@ pub enum PdError {
     /// `CString` or `CStr` conversion error.
     #[error(transparent)]
     StringConversion(#[from] StringConversionError),
+    /// An error occurred related to MIDI messages.
+    #[error(transparent)]
+    MidiError(#[from] MidiError),
+    /// An error occurred during offline rendering.
+    #[error(transparent)]
+    RenderError(#[from] RenderError),
+    /// An error occurred while processing audio buffers.
+    #[error(transparent)]
+    ProcessError(#[from] ProcessError),
+    /// An error occurred while parsing a pd file.
+    #[error(transparent)]
+    PatchParseError(#[from] PatchParseError),
+    /// An error occurred while scheduling a message.
+    #[error(transparent)]
+    ScheduleError(#[from] ScheduleError),
+    /// An error occurred related to objects written in Rust.
+    #[error(transparent)]
+    ObjectError(#[from] ObjectError),
 }
 
 /// Errors related to initialization.
@@ -104,6 +122,23 @@ pub enum PatchLifeCycleError {
     /// The path to the patch which are being tried to open is invalid.
     #[error("The path you have provided does not exist in the file system. Path: {0}")]
     PathDoesNotExist(String),
+    /// A path in a patch bundle is absolute or leaves the bundle with `..`.
+    #[error("Paths in a patch bundle need to be relative and can not contain `..`. Path: {0}")]
+    InvalidPathInBundle(String),
+    /// A file of a patch bundle could not be written to its directory.
+    #[error("The file {path} of the patch bundle could not be written: {msg}")]
+    FailedToWriteBundle { path: String, msg: String },
+    /// The file which is tried to be opened is not in the patch bundle.
+    #[error("The file is not in the patch bundle. Path: {0}")]
+    FileNotInBundle(String),
+    /// A watched patch could not be opened again, it keeps running and its file is checked again on the next reload.
+    #[error("The patch {id} could not be reloaded: {source}")]
+    FailedToReloadPatch {
+        /// The identifier of the patch.
+        id: crate::types::PatchId,
+        /// The error which occurred while reopening the patch.
+        source: Box<PdError>,
+    },
     /// An error occurred related to string conversion.
     ///
     /// `CString` or `CStr` conversion error.
@@ -160,6 +195,9 @@ pub enum SendError {
     /// Message was started while another message was already started.
     #[error("Message must be submitted before starting a new message.")]
     MessageAlreadyStarted,
+    /// The MIDI message which is being sent is not valid.
+    #[error(transparent)]
+    InvalidMidi(#[from] MidiError),
 }
 
 /// Errors related to subscription to senders in a pd patch.
@@ -203,6 +241,15 @@ pub enum ArrayError {
     /// The position in the array which is tried to be written is out of bounds.
     #[error("The position in array which you're trying to write is out of bounds.")]
     OutOfBounds,
+    /// The slice which is read to doesn't have the length of the range which is read.
+    #[error("Expected a slice of {expected} elements, found {found}.")]
+    LengthMismatch { expected: usize, found: usize },
+    /// The WAV file doesn't have the channel which is being loaded.
+    #[error("The channel {channel} is out of range, the file has {channels} channels.")]
+    ChannelOutOfRange { channel: usize, channels: u16 },
+    /// An error occurred while reading or writing a WAV file.
+    #[error(transparent)]
+    Wav(#[from] hound::Error),
     /// An error occurred related to string conversion.
     ///
     /// `CString` or `CStr` conversion error.
@@ -228,6 +275,274 @@ pub enum InstanceError {
     NoCurrentInstanceSet,
 }
 
+/// Errors related to MIDI messages.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum MidiError {
+    /// The channel is not in the range of `0-15`.
+    #[error("MIDI channel {0} is out of range, channels are in the range of 0-15.")]
+    ChannelOutOfRange(u8),
+    /// A data byte is not in the range of `0-127`.
+    #[error("MIDI data byte {0} is out of range, data bytes are in the range of 0-127.")]
+    DataOutOfRange(u8),
+    /// The pitch bend value is not in the range of `-8192..=8191`.
+    #[error("Pitch bend value {0} is out of range, pitch bend is in the range of -8192..=8191.")]
+    PitchBendOutOfRange(i16),
+    /// The raw bytes do not start with a status byte.
+    #[error("Raw MIDI bytes must start with a status byte.")]
+    MissingStatus,
+    /// The raw bytes do not have the amount of data bytes the status requires.
+    #[error("Raw MIDI message has the wrong amount of data bytes for its status.")]
+    IncompleteMessage,
+    /// The status byte is not a channel voice message.
+    #[error("MIDI status byte {0:#04X} is not a channel voice message.")]
+    UnsupportedStatus(u8),
+}
+
+/// Errors related to offline rendering.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum RenderError {
+    /// DSP is not turned on for the instance which is being rendered.
+    #[error("DSP needs to be turned on before rendering.")]
+    DspInactive,
+    /// The instance which is being rendered has no output channels.
+    #[error("The instance which is being rendered needs at least one output channel.")]
+    NoOutputChannels,
+    /// The channel count of the input file does not match the input channels of the instance.
+    #[error("The input file has {found} channels but the instance has {expected} input channels.")]
+    InputChannelMismatch { expected: i32, found: u16 },
+    /// The sample rate of the input file does not match the sample rate of the instance.
+    #[error("The input file has the sample rate of {found} but the instance runs at {expected}.")]
+    SampleRateMismatch { expected: u32, found: u32 },
+    /// An error occurred while reading or writing a WAV file.
+    #[error(transparent)]
+    Wav(#[from] hound::Error),
+}
+
+/// Errors related to scheduling messages.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum ScheduleError {
+    /// The scheduler which receives the messages is dropped.
+    #[error("The scheduler which receives the messages is dropped.")]
+    SchedulerDropped,
+}
+
+/// Errors related to objects written in Rust.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum ObjectError {
+    /// A class with the same name is already registered.
+    #[error("A class named `{0}` is already registered.")]
+    AlreadyRegistered(String),
+    /// pd could not create the class.
+    #[error("Failed to register the class named `{0}`.")]
+    FailedToRegister(String),
+    /// The object doesn't have an outlet with the index.
+    #[error("The outlet {index} is out of range, the object has {outlets} outlets.")]
+    OutletOutOfRange { index: usize, outlets: usize },
+    /// An error occurred related to string conversion.
+    ///
+    /// `CString` or `CStr` conversion error.
+    #[error(transparent)]
+    StringConversion(#[from] StringConversionError),
+}
+
+/// Errors related to testing patches against golden files.
+#[cfg(feature = "testing")]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum TestingError {
+    /// The golden file doesn't exist, it could be written by setting `LIBPD_RS_UPDATE_GOLDEN`.
+    #[error("The golden file {0} doesn't exist, set LIBPD_RS_UPDATE_GOLDEN to write it.")]
+    GoldenMissing(std::path::PathBuf),
+    /// The channels and the sample rate of the golden audio are not the ones of the output.
+    #[error("Expected {expected:?} channels and sample rate, found {found:?}.")]
+    SpecMismatch {
+        expected: (u16, u32),
+        found: (u16, u32),
+    },
+    /// The golden audio doesn't have as many samples as the output.
+    #[error("Expected {expected} samples, found {found}.")]
+    LengthMismatch { expected: usize, found: usize },
+    /// A sample of the output differs from the golden audio more than the tolerance.
+    #[error("The sample at frame {frame} of channel {channel} is {found}, expected {expected}.")]
+    AudioMismatch {
+        frame: usize,
+        channel: usize,
+        expected: f32,
+        found: f32,
+    },
+    /// A message of the output differs from the golden messages.
+    #[error("The message at {index} is {found}, expected {expected}.")]
+    MessageMismatch {
+        index: usize,
+        expected: String,
+        found: String,
+    },
+    /// An error occurred in the instance which runs the patch.
+    #[error(transparent)]
+    Pd(#[from] PdError),
+    /// An error occurred while reading or writing a WAV file.
+    #[error(transparent)]
+    Wav(#[from] hound::Error),
+    /// An error occurred while reading or writing a JSON file.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// An error occurred while reading or writing a file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Errors related to the FUDI codec and bridge.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum FudiError {
+    /// A message is not valid UTF-8.
+    #[error("A message is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] core::str::Utf8Error),
+    /// The text ends before the message is terminated with a semicolon.
+    #[error("The message {0} is not terminated with a semicolon.")]
+    IncompleteMessage(String),
+    /// The message doesn't start with the name of a receiver.
+    #[error("The message {0:?} doesn't start with the name of a receiver.")]
+    MissingReceiver(Vec<crate::Atom>),
+    /// An error occurred while sending a message to pd.
+    #[error(transparent)]
+    Pd(#[from] PdError),
+    /// An error occurred on a socket.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Errors related to the OSC codec and bridge.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum OscError {
+    /// The packet ends before an element is complete.
+    #[error("The packet ends before an element is complete.")]
+    UnexpectedEnd,
+    /// The packet is neither a message nor a bundle.
+    #[error("The packet is neither a message nor a bundle.")]
+    InvalidPacket,
+    /// A blob or a bundle element has a negative size.
+    #[error("A blob or a bundle element has the negative size {0}.")]
+    InvalidSize(i32),
+    /// An argument has a type which is not supported.
+    #[error("The type tag {0} is not supported.")]
+    UnsupportedType(char),
+    /// A string is not valid UTF-8.
+    #[error("A string is not valid UTF-8: {0}")]
+    InvalidUtf8(#[from] core::str::Utf8Error),
+    /// An error occurred while sending a message to pd.
+    #[error(transparent)]
+    Pd(#[from] PdError),
+    /// An error occurred on the socket.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Errors related to requests which wait for a reply from pd.
+#[cfg(feature = "async")]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum RequestError {
+    /// No reply arrived in time.
+    #[error("No reply from {reply_source} arrived in {timeout:?}.")]
+    Timeout {
+        /// The source the reply was expected from.
+        reply_source: String,
+        /// How long the request waited.
+        timeout: core::time::Duration,
+    },
+    /// The instance is dropped before a reply arrived.
+    #[error("The instance is dropped before a reply arrived.")]
+    Closed,
+    /// An error occurred while sending the request.
+    #[error(transparent)]
+    Pd(#[from] PdError),
+}
+
+/// Errors related to deserializing a list of atoms into a Rust type.
+#[cfg(feature = "serde")]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum DeserializeError {
+    /// The list ended before the value is complete.
+    #[error("The list ended before the value is complete.")]
+    EndOfList,
+    /// Atoms are left in the list after the value.
+    #[error("{0} atoms are left in the list after the value.")]
+    TrailingAtoms(usize),
+    /// A float is expected but the atom is a symbol.
+    #[error("Expected a float, found the symbol {0}.")]
+    ExpectedFloat(String),
+    /// A symbol is expected but the atom is a float.
+    #[error("Expected a symbol, found the float {0}.")]
+    ExpectedSymbol(f64),
+    /// A character is expected but the symbol is not a single character.
+    #[error("Expected a single character, found the symbol {0}.")]
+    ExpectedChar(String),
+    /// The float is not an integer or it doesn't fit in the integer type.
+    #[error("The float {0} is not an integer of the expected type.")]
+    NotAnInteger(f64),
+    /// The type can not be represented with atoms.
+    #[error("{0} can not be deserialized from atoms.")]
+    Unsupported(&'static str),
+    /// An error reported by the type which is deserialized.
+    #[error("{0}")]
+    Custom(String),
+}
+
+/// Errors related to the buffers which are processed.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum ProcessError {
+    /// The amount of input channels is not the one the instance is configured with.
+    #[error("Expected {expected} input channels, found {found}.")]
+    InputChannelMismatch { expected: usize, found: usize },
+    /// The amount of output channels is not the one the instance is configured with.
+    #[error("Expected {expected} output channels, found {found}.")]
+    OutputChannelMismatch { expected: usize, found: usize },
+    /// The channels of a planar buffer do not have the same length.
+    #[error("Every channel of a planar buffer needs to have the same length.")]
+    UnevenChannelLengths,
+    /// The amount of frames is not a multiple of the block size.
+    #[error("{frames} frames is not a multiple of the block size {block_size}.")]
+    PartialBlock { frames: usize, block_size: usize },
+    /// The input buffer doesn't have the length the ticks and the input channels require.
+    #[error("Expected an input buffer of {expected} samples, found {found}.")]
+    InputLengthMismatch { expected: usize, found: usize },
+    /// The output buffer doesn't have the length the ticks and the output channels require.
+    #[error("Expected an output buffer of {expected} samples, found {found}.")]
+    OutputLengthMismatch { expected: usize, found: usize },
+    /// The amount of ticks to process is negative.
+    #[error("The amount of ticks to process can not be negative, found {0}.")]
+    NegativeTicks(i32),
+}
+
+/// Errors related to parsing pd files.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum PatchParseError {
+    /// The file doesn't start with a `#N canvas` record.
+    #[error("A pd file needs to start with a `#N canvas` record.")]
+    MissingRootCanvas,
+    /// A record is not terminated with `;`.
+    #[error("The record which starts at line {line} is not terminated with `;`.")]
+    UnterminatedRecord { line: usize },
+    /// A record doesn't have the arguments its kind requires.
+    #[error("The record at line {line} is not valid: {record}")]
+    InvalidRecord { line: usize, record: String },
+    /// A `#X restore` record appears without a subpatch to close.
+    #[error("The `#X restore` record at line {line} has no subpatch to close.")]
+    UnbalancedRestore { line: usize },
+    /// The file ends before all subpatches are closed.
+    #[error("The file ends before all subpatches are closed with `#X restore`.")]
+    UnclosedSubpatch,
+}
+
 /// Errors related to string conversion.
 ///
 /// `CString` or `CStr` conversion error.
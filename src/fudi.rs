@@ -0,0 +1,599 @@
+use std::{
+    io::{self, Read, Write},
+    mem,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, PoisonError,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    error::{FudiError, PdError},
+    patch::float,
+    types::{AddressedMessage, PdMessage},
+    Atom, Pd,
+};
+
+/// How long the threads of a bridge wait on a socket before they check if the bridge is dropped.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a message to a TCP client may block [`FudiBridge::forward`] before the client is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The largest payload of a UDP datagram.
+pub(crate) const MAX_DATAGRAM: usize = 65_507;
+
+/// Encodes a list of atoms as a FUDI message, terminated with a semicolon and a newline.
+///
+/// Symbols are escaped the way pd does, so `$`, `;`, `,`, `\` and whitespace in them survive the trip.
+/// Symbols which look like numbers are escaped too, so they are not read back as floats.
+///
+/// # Example
+/// ```
+/// use libpd_rs::{fudi, Atom};
+///
+/// let message = fudi::encode(&[Atom::from("pitch"), Atom::Float(440.0)]);
+/// assert_eq!(message, "pitch 440;\n");
+/// ```
+pub fn encode(atoms: &[Atom]) -> String {
+    let mut out = String::new();
+    for atom in atoms {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        match atom {
+            Atom::Float(value) => out.push_str(&value.to_string()),
+            Atom::Symbol(symbol) => escape(&mut out, symbol),
+        }
+    }
+    out.push_str(";\n");
+    out
+}
+
+/// Decodes every message in the text, each message is a list of atoms.
+///
+/// Messages are terminated with a semicolon or a comma which is not escaped, empty messages are skipped.
+///
+/// # Example
+/// ```
+/// use libpd_rs::{fudi, Atom};
+///
+/// let messages = fudi::decode("pitch 440;\nname a\\ b;\n").unwrap();
+/// assert_eq!(messages[1], vec![Atom::from("name"), Atom::from("a b")]);
+/// ```
+///
+/// # Errors
+///
+/// A list of errors that can occur:
+/// - [`FudiError`]
+///   - [`InvalidUtf8`](crate::error::FudiError::InvalidUtf8)
+///   - [`IncompleteMessage`](crate::error::FudiError::IncompleteMessage)
+pub fn decode(text: &str) -> Result<Vec<Vec<Atom>>, FudiError> {
+    let mut decoder = FudiDecoder::new();
+    decoder.push(text.as_bytes());
+    let messages = decoder.by_ref().collect::<Result<Vec<_>, _>>()?;
+    let rest = String::from_utf8_lossy(&decoder.buffer);
+    if rest.trim().is_empty() {
+        Ok(messages)
+    } else {
+        Err(FudiError::IncompleteMessage(rest.into_owned()))
+    }
+}
+
+/// Decodes FUDI messages from a stream of bytes which may be split anywhere, e.g. the reads from a TCP socket.
+///
+/// Bytes are buffered until a message is complete, every complete message is returned from the iterator.
+///
+/// # Example
+/// ```
+/// use libpd_rs::{fudi::FudiDecoder, Atom};
+///
+/// let mut decoder = FudiDecoder::new();
+/// decoder.push(b"gain 0.");
+/// assert!(decoder.next().is_none());
+/// decoder.push(b"5;\n");
+/// assert_eq!(
+///     decoder.next().unwrap().unwrap(),
+///     vec![Atom::from("gain"), Atom::Float(0.5)]
+/// );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct FudiDecoder {
+    buffer: Vec<u8>,
+}
+
+impl FudiDecoder {
+    /// Creates a decoder with an empty buffer.
+    pub const fn new() -> Self {
+        Self { buffer: Vec::new() }
+    }
+
+    /// Appends bytes to the buffer.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+}
+
+impl Iterator for FudiDecoder {
+    type Item = Result<Vec<Atom>, FudiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let end = message_end(&self.buffer)?;
+            let message: Vec<u8> = self.buffer.drain(..=end).collect();
+            let body = message.split_last().map_or(&[][..], |(_, body)| body);
+            match core::str::from_utf8(body) {
+                Ok(text) => {
+                    let atoms = atoms(text);
+                    if !atoms.is_empty() {
+                        return Some(Ok(atoms));
+                    }
+                }
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+/// Finds the end of the first message, the position of the first `;` or `,` which is not escaped.
+fn message_end(bytes: &[u8]) -> Option<usize> {
+    let mut escaped = false;
+    for (index, byte) in bytes.iter().enumerate() {
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b';' | b',' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the body of a message to atoms, resolving escapes.
+fn atoms(text: &str) -> Vec<Atom> {
+    let mut atoms = Vec::new();
+    let mut characters = text.chars();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut escaped = false;
+    loop {
+        match characters.next() {
+            Some('\\') => {
+                if let Some(next) = characters.next() {
+                    word.push(next);
+                }
+                in_word = true;
+                escaped = true;
+            }
+            Some(character) if !character.is_whitespace() => {
+                word.push(character);
+                in_word = true;
+            }
+            next => {
+                if in_word {
+                    let word = mem::take(&mut word);
+                    // Escaped words are always symbols, like in pd.
+                    atoms.push(if escaped {
+                        Atom::Symbol(word)
+                    } else {
+                        float(&word).map_or(Atom::Symbol(word), Atom::Float)
+                    });
+                }
+                in_word = false;
+                escaped = false;
+                if next.is_none() {
+                    return atoms;
+                }
+            }
+        }
+    }
+}
+
+fn escape(out: &mut String, symbol: &str) {
+    if float(symbol).is_some() {
+        out.push('\\');
+    }
+    for character in symbol.chars() {
+        if matches!(character, '\\' | '$' | ';' | ',') || character.is_whitespace() {
+            out.push('\\');
+        }
+        out.push(character);
+    }
+}
+
+/// How the messages which arrive to a [`FudiBridge`] are sent to pd.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FudiRouting {
+    /// The first atom of a message names the receiver, e.g. `synth note 60;` sends `note 60` to `synth`.
+    ///
+    /// This is the way the messages which start with a semicolon in a message box are sent.
+    #[default]
+    Addressed,
+    /// Every message is sent to the given receiver, like a `[netreceive]` connected to a `[send]`.
+    Receiver(String),
+}
+
+/// Forwards FUDI messages between local sockets and a [`Pd`] instance,
+/// so `[netsend]`, `[netreceive]` and other pd tooling could talk to it.
+///
+/// Messages which arrive are sent to pd according to the [`FudiRouting`] of the bridge.
+/// A message which starts with a symbol is sent with [`send_message_to`](Pd::send_message_to),
+/// one which starts with a float is sent as a list with [`send_list_to`](Pd::send_list_to).
+///
+/// Messages sent to the receivers which are subscribed with [`start_listening_from`](Pd::start_listening_from)
+/// are sent out starting with the name of the receiver, e.g. `from_pd float 1;`.
+/// A `[route from_pd]` after a `[netreceive]` picks them up on the other end.
+///
+/// Sockets are read on background threads which stop when the bridge is dropped,
+/// messages are forwarded in both directions when [`forward`](FudiBridge::forward) is called.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{fudi::FudiBridge, Pd};
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("synth.pd").unwrap();
+/// pd.start_listening_from("from_pd").unwrap();
+///
+/// // Connect from pd with [connect localhost 3000( to a [netsend].
+/// let bridge = FudiBridge::tcp(&mut pd, "127.0.0.1:3000").unwrap();
+/// loop {
+///     // The audio thread calls receive_messages_from_pd on its context.
+///     bridge.forward(&pd).unwrap();
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FudiBridge {
+    local_addr: SocketAddr,
+    routing: FudiRouting,
+    incoming: mpsc::Receiver<Result<Vec<Atom>, FudiError>>,
+    outgoing: mpsc::Receiver<PdMessage>,
+    peers: Peers,
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+/// Where the messages from pd are sent.
+#[derive(Debug)]
+enum Peers {
+    /// Every client which is connected to the listener.
+    Tcp(Arc<Mutex<Vec<TcpStream>>>),
+    /// A single address, UDP has no connections to follow.
+    Udp { socket: UdpSocket, peer: SocketAddr },
+}
+
+impl FudiBridge {
+    /// Listens for TCP connections on the address, like `[netreceive 3000]`.
+    ///
+    /// Messages from pd are sent to every client which is connected.
+    /// A client which doesn't read them fast enough to take a message within 100 milliseconds is disconnected,
+    /// so a stalled client can't hold up [`forward`](FudiBridge::forward).
+    /// Port `0` picks a free port, see [`local_addr`](FudiBridge::local_addr).
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FudiError`]
+    ///   - [`Io`](crate::error::FudiError::Io)
+    pub fn tcp<A: ToSocketAddrs>(pd: &mut Pd, address: A) -> Result<Self, FudiError> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+
+        let (sender, incoming) = mpsc::channel();
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let clients = Arc::clone(&clients);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || accept(&listener, &clients, &sender, &stop))
+        };
+
+        Ok(Self {
+            local_addr,
+            routing: FudiRouting::default(),
+            incoming,
+            outgoing: pd.message_receiver(),
+            peers: Peers::Tcp(clients),
+            stop,
+            threads: vec![thread],
+        })
+    }
+
+    /// Listens for UDP datagrams on the address, like `[netreceive -u 3000]`,
+    /// and sends the messages from pd to the peer, like `[netsend -u]` connected to it.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FudiError`]
+    ///   - [`Io`](crate::error::FudiError::Io)
+    pub fn udp<A: ToSocketAddrs, P: ToSocketAddrs>(
+        pd: &mut Pd,
+        address: A,
+        peer: P,
+    ) -> Result<Self, FudiError> {
+        let socket = UdpSocket::bind(address)?;
+        let local_addr = socket.local_addr()?;
+        let peer = peer
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No address to send to."))?;
+        let reader = socket.try_clone()?;
+        reader.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let (sender, incoming) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || receive_datagrams(&reader, &sender, &stop))
+        };
+
+        Ok(Self {
+            local_addr,
+            routing: FudiRouting::default(),
+            incoming,
+            outgoing: pd.message_receiver(),
+            peers: Peers::Udp { socket, peer },
+            stop,
+            threads: vec![thread],
+        })
+    }
+
+    /// Sets how the messages which arrive are sent to pd, [`FudiRouting::Addressed`] by default.
+    #[must_use]
+    pub fn routing(mut self, routing: FudiRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Returns the address the bridge listens on.
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Sends the messages from pd out and the messages which arrived since the last call to pd.
+    ///
+    /// Messages from pd are collected while its queues are drained with
+    /// [`receive_messages_from_pd`](crate::PdAudioContext::receive_messages_from_pd).
+    ///
+    /// Returns the amount of messages which are sent to pd.
+    /// Messages after the one which fails are kept for the next call.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FudiError`]
+    ///   - [`InvalidUtf8`](crate::error::FudiError::InvalidUtf8)
+    ///   - [`MissingReceiver`](crate::error::FudiError::MissingReceiver)
+    ///   - [`Pd`](crate::error::FudiError::Pd)
+    ///   - [`Io`](crate::error::FudiError::Io)
+    pub fn forward(&self, pd: &Pd) -> Result<usize, FudiError> {
+        for message in self.outgoing.try_iter() {
+            if let Some(message) = AddressedMessage::from_pd_message(&message) {
+                let atoms: Vec<Atom> = [
+                    Atom::Symbol(message.receiver),
+                    Atom::Symbol(message.selector),
+                ]
+                .into_iter()
+                .chain(message.atoms)
+                .collect();
+                self.peers.send(encode(&atoms).as_bytes())?;
+            }
+        }
+
+        let mut forwarded = 0;
+        for message in self.incoming.try_iter() {
+            self.send_to_pd(pd, message?)?;
+            forwarded += 1;
+        }
+        Ok(forwarded)
+    }
+
+    fn send_to_pd(&self, pd: &Pd, atoms: Vec<Atom>) -> Result<(), FudiError> {
+        let (receiver, list) = match &self.routing {
+            FudiRouting::Addressed => match atoms.split_first() {
+                Some((Atom::Symbol(receiver), list)) => (receiver.as_str(), list),
+                _ => return Err(FudiError::MissingReceiver(atoms)),
+            },
+            FudiRouting::Receiver(receiver) => (receiver.as_str(), atoms.as_slice()),
+        };
+        match list.split_first() {
+            None => pd.send_bang_to(receiver).map_err(PdError::from)?,
+            Some((Atom::Symbol(selector), args)) => {
+                pd.send_message_to(receiver, selector.as_str(), args)?;
+            }
+            Some((Atom::Float(_), _)) => pd.send_list_to(receiver, list)?,
+        }
+        Ok(())
+    }
+}
+
+impl Drop for FudiBridge {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            // A thread which panicked has nothing left to clean up.
+            thread.join().ok();
+        }
+    }
+}
+
+impl Peers {
+    fn send(&self, bytes: &[u8]) -> Result<(), FudiError> {
+        match self {
+            Self::Tcp(clients) => {
+                // Clients which are gone or too slow to take the message are dropped, the others keep receiving.
+                // A client whose write times out may have received a part of the message, so it can't be kept.
+                clients
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .retain_mut(|client| client.write_all(bytes).is_ok());
+            }
+            Self::Udp { socket, peer } => {
+                socket.send_to(bytes, peer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Accepts clients until the bridge is dropped, each client is read on its own thread.
+fn accept(
+    listener: &TcpListener,
+    clients: &Mutex<Vec<TcpStream>>,
+    sender: &mpsc::Sender<Result<Vec<Atom>, FudiError>>,
+    stop: &Arc<AtomicBool>,
+) {
+    let mut readers = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // Accepted streams may inherit the non blocking mode of the listener on some platforms.
+                if stream.set_nonblocking(false).is_err()
+                    || stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err()
+                {
+                    continue;
+                }
+                let Ok(writer) = stream.try_clone() else {
+                    continue;
+                };
+                clients
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(writer);
+                let sender = sender.clone();
+                let stop = Arc::clone(stop);
+                readers.push(thread::spawn(move || read_stream(stream, &sender, &stop)));
+            }
+            // Errors of a single connection, e.g. one which is aborted before it is accepted, don't stop the listener.
+            Err(_) => thread::sleep(POLL_INTERVAL),
+        }
+    }
+    for reader in readers {
+        reader.join().ok();
+    }
+}
+
+fn read_stream(
+    mut stream: TcpStream,
+    sender: &mpsc::Sender<Result<Vec<Atom>, FudiError>>,
+    stop: &AtomicBool,
+) {
+    if stream.set_read_timeout(Some(POLL_INTERVAL)).is_err() {
+        return;
+    }
+    let mut decoder = FudiDecoder::new();
+    let mut buffer = [0_u8; 4096];
+    while !stop.load(Ordering::Relaxed) {
+        match stream.read(&mut buffer) {
+            Ok(0) => return,
+            Ok(read) => {
+                decoder.push(buffer.get(..read).unwrap_or_default());
+                for message in decoder.by_ref() {
+                    if sender.send(message).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(err) if is_timeout(&err) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+fn receive_datagrams(
+    socket: &UdpSocket,
+    sender: &mpsc::Sender<Result<Vec<Atom>, FudiError>>,
+    stop: &AtomicBool,
+) {
+    let mut buffer = vec![0_u8; MAX_DATAGRAM];
+    while !stop.load(Ordering::Relaxed) {
+        match socket.recv_from(&mut buffer) {
+            Ok((read, _)) => {
+                // Every datagram holds whole messages.
+                let mut decoder = FudiDecoder::new();
+                decoder.push(buffer.get(..read).unwrap_or_default());
+                for message in decoder {
+                    if sender.send(message).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(err) if is_timeout(&err) => {}
+            // Errors like the connection reset which Windows reports for an earlier datagram to a closed port
+            // are about a single datagram, the socket is read until the bridge is dropped.
+            Err(_) => thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+pub(crate) fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+
+    use super::*;
+
+    #[test]
+    fn encode_escapes_symbols() {
+        let atoms = [
+            Atom::from("set"),
+            Atom::from("$1-freq"),
+            Atom::from("a b;c,d"),
+            Atom::from("12"),
+            Atom::Float(-0.25),
+        ];
+        assert_eq!(encode(&atoms), "set \\$1-freq a\\ b\\;c\\,d \\12 -0.25;\n");
+        assert_eq!(decode(&encode(&atoms)).unwrap(), vec![atoms.to_vec()]);
+    }
+
+    #[test]
+    fn decode_splits_messages() {
+        let messages = decode("a 1, b 2;\n\n;c;").unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                vec![Atom::from("a"), Atom::Float(1.0)],
+                vec![Atom::from("b"), Atom::Float(2.0)],
+                vec![Atom::from("c")],
+            ]
+        );
+        assert!(matches!(
+            decode("a 1; b"),
+            Err(FudiError::IncompleteMessage(rest)) if rest == " b"
+        ));
+    }
+
+    #[test]
+    fn decoder_buffers_partial_messages() {
+        let mut decoder = FudiDecoder::new();
+        decoder.push("sym é".as_bytes().get(..5).unwrap());
+        assert!(decoder.next().is_none());
+        decoder.push("sym é".as_bytes().get(5..).unwrap());
+        decoder.push(b";\nx\\;y;");
+        assert_eq!(
+            decoder.next().unwrap().unwrap(),
+            vec![Atom::from("sym"), Atom::from("é")]
+        );
+        assert_eq!(decoder.next().unwrap().unwrap(), vec![Atom::from("x;y")]);
+        assert!(decoder.next().is_none());
+
+        decoder.push(&[0xff, b';']);
+        assert!(matches!(
+            decoder.next(),
+            Some(Err(FudiError::InvalidUtf8(_)))
+        ));
+    }
+}
@@ -389,12 +389,16 @@ use crate::error::{AudioInitializationError, PatchLifeCycleError, StringConversi
 
 use crate::{
     error::{InitializationError, IoError},
-    types::PatchFileHandle,
+    types::{FailedObject, PatchFileHandle},
 };
 
-use std::ffi::{self, CString};
+use libpd_sys::{t_gobj, t_object};
+use std::collections::HashMap;
+use std::ffi::{self, CStr, CString};
 use std::path::{Path, PathBuf};
-use std::{env, path};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::thread::{self, ThreadId};
+use std::{env, mem, path, ptr};
 
 /// Initializes libpd.
 ///
@@ -461,10 +465,21 @@ pub fn release_internal_queues() {
     };
 }
 
+/// The search paths which are added to each instance, by the address of the instance.
+///
+/// libpd can not list or remove a single search path, they are kept here to add the others again.
+static SEARCH_PATHS: LazyLock<Mutex<HashMap<usize, Vec<CString>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Clears all the paths where libpd searches for patches and assets.
 ///
 /// Initializing an instance also clears the search paths.
 pub fn clear_search_paths() {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    SEARCH_PATHS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&instance);
     unsafe {
         libpd_sys::libpd_clear_search_path();
     }
@@ -491,10 +506,45 @@ pub fn add_to_search_paths<T: AsRef<Path>>(path: T) -> Result<(), IoError> {
         let c_path =
             CString::new(&*path.as_ref().to_string_lossy()).map_err(StringConversionError::from)?;
         libpd_sys::libpd_add_to_search_path(c_path.as_ptr());
+        let instance = libpd_sys::libpd_this_instance() as usize;
+        SEARCH_PATHS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(instance)
+            .or_default()
+            .push(c_path);
         Ok(())
     }
 }
 
+/// Removes a path which is added with [`add_to_search_paths`] from the search paths of the current instance.
+///
+/// libpd can only clear the search paths, so the other ones are added again in the order they are added.
+pub(crate) fn remove_from_search_paths<T: AsRef<Path>>(path: T) {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    let mut search_paths = SEARCH_PATHS.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(paths) = search_paths.get_mut(&instance) else {
+        return;
+    };
+    let removed = path.as_ref().to_string_lossy();
+    paths.retain(|path| path.to_string_lossy() != removed);
+    unsafe {
+        libpd_sys::libpd_clear_search_path();
+        for path in paths.iter() {
+            libpd_sys::libpd_add_to_search_path(path.as_ptr());
+        }
+    }
+}
+
+/// Forgets the search paths of the current instance, which is about to be freed.
+pub(crate) fn forget_search_paths() {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    SEARCH_PATHS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&instance);
+}
+
 /// Opens a pd patch.
 ///
 /// The argument should be an absolute path to the patch file.
@@ -648,6 +698,229 @@ pub fn get_dollar_zero(handle: &PatchFileHandle) -> Result<i32, PatchLifeCycleEr
     }
 }
 
+/// Finds the objects of an open patch which pd couldn't create.
+///
+/// pd keeps an object which it couldn't create in the patch as an empty box with its text,
+/// e.g. for a typo or a missing external, and only prints an error for it while the patch is loaded.
+/// Subpatches and abstractions are searched too.
+///
+/// The [`errors`](FailedObject::errors) of the objects are empty since the output of pd is not captured here,
+/// [`Pd::failed_objects`](crate::Pd::failed_objects) lists them with what pd printed while the patch is opened.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::functions::{failed_objects, open_patch};
+///
+/// let handle = open_patch("tests/patches/simple.pd").unwrap();
+/// for object in failed_objects(&handle) {
+///     eprintln!("[{}] at {}, {}", object.text, object.x, object.y);
+/// }
+/// ```
+pub fn failed_objects(handle: &PatchFileHandle) -> Vec<FailedObject> {
+    failed_objects_with_errors(handle, &CapturedOutput::default())
+}
+
+/// Finds the objects of an open patch which pd couldn't create like [`failed_objects`],
+/// attaching what pd printed for each of them while the patch is opened.
+pub(crate) fn failed_objects_with_errors(
+    handle: &PatchFileHandle,
+    output: &CapturedOutput,
+) -> Vec<FailedObject> {
+    let mut failed = Vec::new();
+    let canvas = handle.as_mut_ptr().cast::<GlistHead>();
+    if !canvas.is_null() {
+        collect_failed_objects(canvas, &mut Vec::new(), output, &mut failed);
+    }
+    failed
+}
+
+/// The beginning of `t_glist` in `g_canvas.h`, which is not a part of the public headers of pd.
+///
+/// It matches pd 0.55 which libpd-sys 0.3.4 builds, the list of objects of a canvas follows its object header
+/// since the first versions of pd.
+#[repr(C)]
+struct GlistHead {
+    object: t_object,
+    list: *mut t_gobj,
+}
+
+// `gl_list` needs to be right after the `t_object` of libpd-sys, with nothing in between.
+const _: () = assert!(mem::offset_of!(GlistHead, list) == mem::size_of::<t_object>());
+const _: () = assert!(
+    mem::size_of::<GlistHead>() == mem::size_of::<t_object>() + mem::size_of::<*mut t_gobj>()
+);
+
+fn collect_failed_objects(
+    canvas: *mut GlistHead,
+    canvases: &mut Vec<String>,
+    output: &CapturedOutput,
+    failed: &mut Vec<FailedObject>,
+) {
+    let mut previous = ptr::null_mut();
+    let mut gobj = unsafe { (*canvas).list };
+    while !gobj.is_null() {
+        let class = unsafe { CStr::from_ptr(libpd_sys::class_getname((*gobj).g_pd)) };
+        let object = gobj.cast::<t_object>();
+        match class.to_bytes() {
+            // Objects which couldn't be created are left as instances of the class of comments.
+            b"text" if unsafe { (*object).te_type() } == libpd_sys::T_OBJECT => {
+                failed.push(FailedObject {
+                    text: object_text(object),
+                    x: i32::from(unsafe { (*object).te_xpix }),
+                    y: i32::from(unsafe { (*object).te_ypix }),
+                    canvases: canvases.clone(),
+                    errors: output.lines_before(canvas, previous),
+                });
+            }
+            b"canvas" => {
+                canvases.push(object_text(object));
+                collect_failed_objects(gobj.cast(), canvases, output, failed);
+                canvases.pop();
+            }
+            _ => {}
+        }
+        previous = gobj;
+        gobj = unsafe { (*gobj).g_next };
+    }
+}
+
+/// The text of an object as it is typed in its box.
+fn object_text(object: *mut t_object) -> String {
+    let binbuf = unsafe { (*object).te_binbuf };
+    if binbuf.is_null() {
+        return String::new();
+    }
+    let mut text = std::ptr::null_mut();
+    let mut length = 0;
+    unsafe { libpd_sys::binbuf_gettext(binbuf, &mut text, &mut length) };
+    if text.is_null() {
+        return String::new();
+    }
+    #[expect(
+        clippy::cast_sign_loss,
+        reason = "pd never returns a negative length for a text."
+    )]
+    let length = length as usize;
+    let bytes = unsafe { std::slice::from_raw_parts(text.cast::<u8>(), length) };
+    let text_owned = String::from_utf8_lossy(bytes).into_owned();
+    unsafe { libpd_sys::freebytes(text.cast(), length) };
+    text_owned
+}
+
+/// What pd printed while a patch is opened, see [`capture_output`].
+#[derive(Debug, Default)]
+pub(crate) struct CapturedOutput {
+    /// The pieces pd printed in order.
+    ///
+    /// The ones which are printed by the thread which opens the patch are kept with the canvas objects are created in
+    /// and the last object in it at the time, the object which is created next is the one the piece belongs to.
+    pieces: Vec<(String, Option<(usize, usize)>)>,
+}
+
+impl CapturedOutput {
+    /// The lines which are printed while the object after `previous` is created in the canvas.
+    fn lines_before(&self, canvas: *mut GlistHead, previous: *mut t_gobj) -> Vec<String> {
+        let position = Some((canvas as usize, previous as usize));
+        let text: String = self
+            .pieces
+            .iter()
+            .filter(|(_, at)| *at == position)
+            .map(|(piece, _)| piece.as_str())
+            .collect();
+        text.lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+/// The instances which capture their output with the thread which opens a patch, by the address of the instance.
+static CAPTURED_OUTPUTS: LazyLock<Mutex<HashMap<usize, (ThreadId, CapturedOutput)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Calls a function which opens a patch in the current instance, collecting everything pd prints meanwhile.
+///
+/// The print hook of the instance is replaced with [`libpd_set_printhook`](libpd_sys::libpd_set_printhook)
+/// for the duration of the call, under the lock of the instance.
+/// The hook of the message queue has no public getter, it is installed again with
+/// [`libpd_queued_init`](libpd_sys::libpd_queued_init) which leaves the queue as it is,
+/// and the output is printed through it afterwards so the print handlers receive it as usual.
+pub(crate) fn capture_output<R, F: FnOnce() -> R>(f: F) -> (R, CapturedOutput) {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    {
+        let mut captured = CAPTURED_OUTPUTS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // The output is already captured by an outer call.
+        if instance == 0 || captured.contains_key(&instance) {
+            drop(captured);
+            return (f(), CapturedOutput::default());
+        }
+        captured.insert(
+            instance,
+            (thread::current().id(), CapturedOutput::default()),
+        );
+    }
+    unsafe {
+        libpd_sys::sys_lock();
+        libpd_sys::libpd_set_printhook(Some(capturing_print_hook));
+        libpd_sys::sys_unlock();
+    }
+
+    let result = f();
+
+    // pd prints under the lock, so nothing is printed between taking the output and installing the hook again.
+    unsafe { libpd_sys::sys_lock() };
+    let output = CAPTURED_OUTPUTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&instance)
+        .map(|(_, output)| output)
+        .unwrap_or_default();
+    unsafe {
+        libpd_sys::libpd_queued_init();
+        for (piece, _) in &output.pieces {
+            if let Ok(piece) = CString::new(piece.as_str()) {
+                libpd_sys::startpost(c"%s".as_ptr(), piece.as_ptr());
+            }
+        }
+        libpd_sys::sys_unlock();
+    }
+    (result, output)
+}
+
+unsafe extern "C" fn capturing_print_hook(piece: *const ffi::c_char) {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    let piece = unsafe { CStr::from_ptr(piece) }
+        .to_string_lossy()
+        .into_owned();
+    let mut captured = CAPTURED_OUTPUTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((thread, output)) = captured.get_mut(&instance) {
+        // Other threads may print meanwhile, e.g. the audio thread between ticks, which is not related to the patch.
+        let position = (*thread == thread::current().id()).then(creating_position);
+        output.pieces.push((piece, position));
+    }
+}
+
+/// The canvas objects are created in and the last object in it, the object which is created next follows it.
+///
+/// pd adds an object to its canvas only after it is created and its errors are printed.
+fn creating_position() -> (usize, usize) {
+    let canvas = unsafe { libpd_sys::canvas_getcurrent() }.cast::<GlistHead>();
+    if canvas.is_null() {
+        return (0, 0);
+    }
+    let mut last = ptr::null_mut::<t_gobj>();
+    let mut gobj = unsafe { (*canvas).list };
+    while !gobj.is_null() {
+        last = gobj;
+        gobj = unsafe { (*gobj).g_next };
+    }
+    (canvas as usize, last as usize)
+}
+
 /// Returns pd's fixed block size which is `64` by default.
 ///
 /// The number of frames per 1 pd tick.
@@ -761,6 +1034,9 @@ pub mod util {
     /// Find the number of pd ticks according to the case.
     ///
     /// The calculation is `buffer_size / (block_size * channels)`
+    ///
+    /// Samples which do not fill a whole tick are truncated,
+    /// use a [`BlockAdapter`](crate::adapter::BlockAdapter) for buffers which are not a multiple of the block size.
     #[expect(
         clippy::integer_division,
         reason = "This function is used to calculate the number of ticks. We don't need floating point precision here."
@@ -34,6 +34,8 @@
 /// To name a few
 /// - If the pd instance is not initialized or set for the thread.
 /// - If input and output buffer sizes are wrong.
+///
+/// [`PdAudioContext::try_process_float`](crate::PdAudioContext::try_process_float) checks the buffer sizes before processing.
 pub fn process_float(ticks: i32, input_buffer: &[f32], output_buffer: &mut [f32]) {
     unsafe {
         libpd_sys::libpd_process_float(ticks, input_buffer.as_ptr(), output_buffer.as_mut_ptr());
@@ -81,6 +83,8 @@ pub fn process_float(ticks: i32, input_buffer: &[f32], output_buffer: &mut [f32]
 /// To name a few
 /// - If the pd instance is not initialized or set for the thread.
 /// - If input and output buffer sizes are wrong.
+///
+/// [`PdAudioContext::try_process_short`](crate::PdAudioContext::try_process_short) checks the buffer sizes before processing.
 pub fn process_short(ticks: i32, input_buffer: &[i16], output_buffer: &mut [i16]) {
     unsafe {
         libpd_sys::libpd_process_short(ticks, input_buffer.as_ptr(), output_buffer.as_mut_ptr());
@@ -123,6 +127,8 @@ pub fn process_short(ticks: i32, input_buffer: &[i16], output_buffer: &mut [i16]
 /// To name a few
 /// - If the pd instance is not initialized or set for the thread.
 /// - If input and output buffer sizes are wrong.
+///
+/// [`PdAudioContext::try_process_double`](crate::PdAudioContext::try_process_double) checks the buffer sizes before processing.
 pub fn process_double(ticks: i32, input_buffer: &[f64], output_buffer: &mut [f64]) {
     unsafe {
         libpd_sys::libpd_process_double(ticks, input_buffer.as_ptr(), output_buffer.as_mut_ptr());
@@ -161,6 +167,8 @@ pub fn process_double(ticks: i32, input_buffer: &[f64], output_buffer: &mut [f64
 /// To name a few
 /// - If the pd instance is not initialized or set for the thread.
 /// - If input and output buffer sizes are wrong.
+///
+/// [`PdAudioContext::try_process_raw`](crate::PdAudioContext::try_process_raw) checks the buffer sizes before processing.
 pub fn process_raw(input_buffer: &[f32], output_buffer: &mut [f32]) {
     unsafe {
         libpd_sys::libpd_process_raw(input_buffer.as_ptr(), output_buffer.as_mut_ptr());
@@ -204,6 +212,8 @@ pub fn process_raw(input_buffer: &[f32], output_buffer: &mut [f32]) {
 /// To name a few
 /// - If the pd instance is not initialized or set for the thread.
 /// - If input and output buffer sizes are wrong.
+///
+/// [`PdAudioContext::try_process_raw_short`](crate::PdAudioContext::try_process_raw_short) checks the buffer sizes before processing.
 pub fn process_raw_short(input_buffer: &[i16], output_buffer: &mut [i16]) {
     unsafe {
         libpd_sys::libpd_process_raw_short(input_buffer.as_ptr(), output_buffer.as_mut_ptr());
@@ -242,6 +252,8 @@ pub fn process_raw_short(input_buffer: &[i16], output_buffer: &mut [i16]) {
 /// To name a few
 /// - If the pd instance is not initialized or set for the thread.
 /// - If input and output buffer sizes are wrong.
+///
+/// [`PdAudioContext::try_process_raw_double`](crate::PdAudioContext::try_process_raw_double) checks the buffer sizes before processing.
 pub fn process_raw_double(input_buffer: &[f64], output_buffer: &mut [f64]) {
     unsafe {
         libpd_sys::libpd_process_raw_double(input_buffer.as_ptr(), output_buffer.as_mut_ptr());
@@ -9,7 +9,11 @@ use libpd_sys::{
 };
 use std::{any::TypeId, ffi::c_void, mem};
 
-use crate::{error::InstanceError, functions};
+use crate::{
+    error::{InstanceError, ObjectError},
+    functions,
+    object::{self, PdObject, PdSignalObject},
+};
 
 type FreeHookCodePtr = *const FnPtr1<'static, *mut c_void, ()>;
 
@@ -198,6 +202,46 @@ impl PdInstance {
         current_instance.pd_instanceno == self.number
     }
 
+    /// Registers an object written in Rust as a class which patches could create with the name.
+    ///
+    /// Classes are shared by every instance in pd, the class is available in all instances after this call.
+    /// Register classes before opening the patches which use them.
+    ///
+    /// This instance is set as the current instance.
+    ///
+    /// See [`PdObject`](crate::object::PdObject) for an example.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AlreadyRegistered`](crate::error::ObjectError::AlreadyRegistered)
+    /// - [`FailedToRegister`](crate::error::ObjectError::FailedToRegister)
+    /// - [`StringConversion`](crate::error::ObjectError::StringConversion)
+    pub fn register_class<T: PdObject>(&self, name: &str) -> Result<(), ObjectError> {
+        self.set_as_current();
+        object::register_class::<T>(name)
+    }
+
+    /// Registers a signal object written in Rust as a class which patches could create with the name.
+    ///
+    /// Classes are shared by every instance in pd, the class is available in all instances after this call.
+    /// Register classes before opening the patches which use them.
+    ///
+    /// This instance is set as the current instance.
+    ///
+    /// See [`PdSignalObject`](crate::object::PdSignalObject) for an example.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AlreadyRegistered`](crate::error::ObjectError::AlreadyRegistered)
+    /// - [`FailedToRegister`](crate::error::ObjectError::FailedToRegister)
+    /// - [`StringConversion`](crate::error::ObjectError::StringConversion)
+    pub fn register_signal_class<T: PdSignalObject>(&self, name: &str) -> Result<(), ObjectError> {
+        self.set_as_current();
+        object::register_signal_class::<T>(name)
+    }
+
     /// Set custom instance data with an optional free hook
     ///
     /// We expose this since it is a library function but I'm not sure if it is useful.
@@ -274,6 +318,7 @@ impl Drop for PdInstance {
         //     libpd_free_instance(pd1);
 
         self.set_as_current();
+        functions::forget_search_paths();
         functions::release_internal_queues();
         unsafe { libpd_free_instance(self.inner) }
     }
@@ -523,7 +523,8 @@ pub mod functions;
 ///
 /// This module exposes the representation of that type as a Rust enum, [`Atom`].
 ///
-/// It also exposes some others to hold file or receiver handles returned from libpd functions.
+/// It also exposes some others to hold file or receiver handles returned from libpd functions
+/// and [`PdMessage`](crate::types::PdMessage) which is delivered through [`Pd::message_receiver`].
 pub mod types;
 
 /// All errors
@@ -532,31 +533,108 @@ pub mod types;
 pub mod error;
 
 /// The atom module contains the Atom enum which is used to represent pd's atom type in Rust.
+///
+/// With the `serde` feature, atoms could be serialized and [`from_atoms`](crate::atom::from_atoms) deserializes a list of atoms into a Rust type.
 pub mod atom;
 
+/// The midi module contains the [`MidiMessage`](crate::midi::MidiMessage) enum which is a typed representation of MIDI channel voice messages.
+///
+/// It also exposes a [`MidiParser`](crate::midi::MidiParser) to decode raw MIDI byte streams coming from any MIDI source.
+pub mod midi;
+
+/// The render module contains [`OfflineRenderer`](crate::render::OfflineRenderer) which renders a patch to a WAV file faster than realtime.
+///
+/// It is useful in CI or batch jobs where there is no audio device.
+pub mod render;
+
+/// The patch module contains a parser and a writer for the pd file format.
+///
+/// A [`Patch`](crate::patch::Patch) could be inspected and transformed before it is handed to [`Pd::eval_patch`].
+/// Unmodified parts of a patch are written back byte for byte.
+pub mod patch;
+
+/// The schedule module contains a [`Scheduler`](crate::schedule::Scheduler) which sends timestamped messages to pd
+/// right before the tick they belong to.
+///
+/// It is an alternative to sending messages right away, which take effect at the start of the next buffer.
+pub mod schedule;
+
+/// The adapter module contains a [`BlockAdapter`](crate::adapter::BlockAdapter) which lets hosts process
+/// buffers of any size, not only multiples of the block size of pd.
+pub mod adapter;
+
+/// The array module contains [`PdArray`](crate::array::PdArray), a handle to a named array in the patches which are open.
+pub mod array;
+
+/// The print module contains [`PrintRecord`](crate::print::PrintRecord), a line printed by pd classified by its level,
+/// see [`Pd::on_print_record`](crate::Pd::on_print_record).
+///
+/// With the `log` or the `tracing` feature records could be forwarded to those crates.
+pub mod print;
+
+/// The fudi module contains a codec for FUDI, the protocol of `[netsend]` and `[netreceive]`,
+/// and a [`FudiBridge`](crate::fudi::FudiBridge) which lets pd tooling talk to the instance over local sockets.
+pub mod fudi;
+
+/// The osc module contains a codec for OSC packets and an [`OscBridge`](crate::osc::OscBridge)
+/// which maps OSC addresses to pd receivers over a local UDP socket.
+pub mod osc;
+
+/// The testing module contains [`PatchTest`](crate::testing::PatchTest) which runs a patch deterministically
+/// and compares its output audio and messages against golden files.
+///
+/// It is enabled with the `testing` feature.
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// The stream module contains [`PdStream`](crate::stream::PdStream), an async stream of the messages sent to a source in pd,
+/// and [`Request`](crate::stream::Request), a future which resolves to the reply of a request,
+/// see [`Pd::stream_from`] and [`Pd::request`].
+///
+/// It is enabled with the `async` feature.
+#[cfg(feature = "async")]
+pub mod stream;
+
+/// The object module contains the [`PdObject`](crate::object::PdObject) and [`PdSignalObject`](crate::object::PdSignalObject)
+/// traits which let objects written in Rust be created in patches like any other pd object.
+pub mod object;
+
 use atom::make_atom_list_from_t_atom_list;
-use error::{PdError, RecieveError, SendError, SizeError, SubscriptionError, C_STR_FAILURE};
-use libffi::high::{
-    ClosureMut1, ClosureMut2, ClosureMut3, ClosureMut4, FnPtr1, FnPtr2, FnPtr3, FnPtr4,
-};
-use libpd_sys::{
-    _pdinstance, t_libpd_aftertouchhook, t_libpd_banghook, t_libpd_controlchangehook,
-    t_libpd_doublehook, t_libpd_floathook, t_libpd_listhook, t_libpd_messagehook,
-    t_libpd_midibytehook, t_libpd_noteonhook, t_libpd_pitchbendhook, t_libpd_polyaftertouchhook,
-    t_libpd_printhook, t_libpd_programchangehook, t_libpd_symbolhook,
+use error::{
+    ArrayError, ObjectError, PdError, ProcessError, RecieveError, SendError, SizeError,
+    SubscriptionError, C_STR_FAILURE,
 };
+use libpd_sys::_pdinstance;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ffi::CStr,
     path::{Path, PathBuf},
-    {fs, os, ptr, slice},
+    sync::{mpsc, Arc, LazyLock, Mutex, PoisonError},
+    time::SystemTime,
+    {env, fs, mem, os, ptr, slice},
 };
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
 
 use crate::{
+    adapter::BlockAdapter,
+    array::PdArray,
     error::PatchLifeCycleError,
     instance::PdInstance,
-    types::{PatchFileHandle, ReceiverHandle},
+    midi::MidiMessage,
+    object::{PdObject, PdSignalObject},
+    patch::PatchBundle,
+    print::{LineAssembler, PrintRecord},
+    schedule::{ScheduleSender, Scheduler},
+    types::{
+        FailedObject, PatchFileHandle, PatchId, PatchInfo, PatchReload, PdMessage, ReceiverHandle,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::{
+    error::RequestError,
+    stream::{PdStream, Request},
+    types::AddressedMessage,
 };
 
 pub use atom::Atom;
@@ -611,6 +689,16 @@ pub use libpd_sys;
 /// To avoid this situation if you use [`Pd`] check its methods, only use them and **not** their function counterparts.
 ///
 /// If you really need to mix the layers, you should read the source of the relevant part before doing so.
+///
+/// # Callbacks
+///
+/// Every [`Pd`] owns its own set of handlers which are registered with the `on_*` methods.
+///
+/// The hooks installed to libpd dispatch on the instance which is being processed,
+/// so handlers of two [`Pd`] values living side by side never overwrite each other.
+///
+/// Registering a handler with a function from [`functions::receive`] for the same instance
+/// replaces this dispatching for that hook, which is another reason not to mix the layers.
 pub struct Pd {
     inner: PdInstance,
     audio_active: bool,
@@ -618,33 +706,17 @@ pub struct Pd {
     output_channels: i32,
     sample_rate: i32,
     sent_message_info: Option<SentMessageInfo>,
-    /// A store to keep track of puredata callback functions to be dropped when the instance goes out of scope.
+    /// The handlers registered for this instance, they are removed from the dispatch table when the instance goes out of scope.
     callbacks: Callbacks,
-    running_patch: Option<PatchFileHandle>,
-    temporary_evaluated_patch: Option<NamedTempFile>,
+    /// The patches which are open in this instance, ordered by the time they are opened.
+    open_patches: BTreeMap<PatchId, OpenPatch>,
+    next_patch_id: u64,
     /// A store to keep track of subscriptions which are made to senders in pd through the app lifecycle.
     pub subscriptions: HashMap<String, ReceiverHandle>,
     /// A store to keep track of paths which are added to pd search paths through the app lifecycle.
     pub search_paths: Vec<PathBuf>,
 }
 
-type PrintHookCodePtr<'a> = *const FnPtr1<'a, *const i8, ()>;
-type BangHookCodePtr<'a> = *const FnPtr1<'a, *const i8, ()>;
-type FloatHookCodePtr<'a> = *const FnPtr2<'a, *const i8, f32, ()>;
-type DoubleHookCodePtr<'a> = *const FnPtr2<'a, *const i8, f64, ()>;
-type SymbolHookCodePtr<'a> = *const FnPtr2<'a, *const i8, *const i8, ()>;
-type ListHookCodePtr<'a> = *const FnPtr3<'a, *const i8, i32, *mut libpd_sys::_atom, ()>;
-type MessageHookCodePtr<'a> =
-    *const FnPtr4<'a, *const i8, *const i8, i32, *mut libpd_sys::_atom, ()>;
-
-type MidiNoteOnCodePtr<'a> = *const FnPtr3<'a, i32, i32, i32, ()>;
-type MidiControlChangeCodePtr<'a> = *const FnPtr3<'a, i32, i32, i32, ()>;
-type MidiProgramChangeCodePtr<'a> = *const FnPtr2<'a, i32, i32, ()>;
-type MidiPitchBendCodePtr<'a> = *const FnPtr2<'a, i32, i32, ()>;
-type MidiAfterTouchCodePtr<'a> = *const FnPtr2<'a, i32, i32, ()>;
-type MidiPolyAfterTouchCodePtr<'a> = *const FnPtr3<'a, i32, i32, i32, ()>;
-type MidiByteCodePtr<'a> = *const FnPtr2<'a, i32, i32, ()>;
-
 const GUARD_FROM_CALLBACK_DURING_DSP: bool = false;
 
 impl Pd {
@@ -681,20 +753,29 @@ impl Pd {
         sample_rate: i32,
     ) -> Result<Self, PdError> {
         let inner = PdInstance::new()?;
-        functions::initialize_audio(input_channels, output_channels, sample_rate)?;
-        Ok(Self {
+        let callbacks = Callbacks::register(&inner);
+        let pd = Self {
             inner,
             audio_active: false,
             input_channels,
             output_channels,
             sample_rate,
             sent_message_info: None,
-            callbacks: Callbacks::new(),
-            running_patch: None,
-            temporary_evaluated_patch: None,
+            callbacks,
+            open_patches: BTreeMap::new(),
+            next_patch_id: 0,
             subscriptions: HashMap::default(),
             search_paths: vec![],
-        })
+        };
+
+        {
+            let _guard = pd.set_as_active_instance();
+            functions::initialize_audio(input_channels, output_channels, sample_rate)?;
+            // Hooks live per instance in libpd, we route all of them to this instance's handlers.
+            install_dispatching_hooks();
+        }
+
+        Ok(pd)
     }
 
     /// Returns a reference to the inner pd instance.
@@ -709,9 +790,88 @@ impl Pd {
 
     /// Creates an audio context for this instance to be easily passed in to the audio thread.
     pub fn audio_context(&self) -> PdAudioContext {
-        PdAudioContext {
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "Pd is configured with positive channel counts."
+        )]
+        let (input_channels, output_channels) =
+            (self.input_channels as usize, self.output_channels as usize);
+        let mut context = PdAudioContext {
             instance: self.inner.clone(),
-        }
+            input_channels,
+            output_channels,
+            float_scratch: PlanarScratch::default(),
+            double_scratch: PlanarScratch::default(),
+        };
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "The block size of pd is a positive constant."
+        )]
+        let frames = functions::block_size() as usize * DEFAULT_PLANAR_TICKS;
+        context.reserve_planar(frames);
+        context
+    }
+
+    /// Creates a [`BlockAdapter`](crate::adapter::BlockAdapter) for this instance to be passed in to the audio thread,
+    /// which processes host buffers of any size.
+    pub fn block_adapter(&self) -> BlockAdapter {
+        BlockAdapter::new(self.audio_context())
+    }
+
+    /// Creates a [`Scheduler`](crate::schedule::Scheduler) for this instance to be passed in to the audio thread
+    /// and a [`ScheduleSender`](crate::schedule::ScheduleSender) to schedule messages to it from any thread.
+    ///
+    /// See [`Scheduler`](crate::schedule::Scheduler) for the accuracy of the scheduling.
+    pub fn scheduler(&self) -> (ScheduleSender, Scheduler) {
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "Pd is configured with a positive sample rate."
+        )]
+        let sample_rate = self.sample_rate as u32;
+        let context = self.audio_context();
+        let (input_channels, output_channels) = (context.input_channels, context.output_channels);
+        Scheduler::new(context, sample_rate, input_channels, output_channels)
+    }
+
+    /// Creates a handle to a named float array in the patches which are open in this instance.
+    ///
+    /// See [`PdArray`](crate::array::PdArray).
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`StringConversion`](crate::error::ArrayError::StringConversion)
+    pub fn array<T: AsRef<str>>(&self, name: T) -> Result<PdArray<'_>, ArrayError> {
+        PdArray::new(self, name.as_ref())
+    }
+
+    /// Registers an object written in Rust as a class which patches could create with the name.
+    ///
+    /// See [`PdInstance::register_class`](crate::instance::PdInstance::register_class).
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AlreadyRegistered`](crate::error::ObjectError::AlreadyRegistered)
+    /// - [`FailedToRegister`](crate::error::ObjectError::FailedToRegister)
+    /// - [`StringConversion`](crate::error::ObjectError::StringConversion)
+    pub fn register_class<T: PdObject>(&self, name: &str) -> Result<(), ObjectError> {
+        self.inner.register_class::<T>(name)
+    }
+
+    /// Registers a signal object written in Rust as a class which patches could create with the name.
+    ///
+    /// See [`PdInstance::register_signal_class`](crate::instance::PdInstance::register_signal_class).
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AlreadyRegistered`](crate::error::ObjectError::AlreadyRegistered)
+    /// - [`FailedToRegister`](crate::error::ObjectError::FailedToRegister)
+    /// - [`StringConversion`](crate::error::ObjectError::StringConversion)
+    pub fn register_signal_class<T: PdSignalObject>(&self, name: &str) -> Result<(), ObjectError> {
+        self.inner.register_signal_class::<T>(name)
     }
 
     /// Set this instance as the current active instance for the thread.
@@ -802,7 +962,7 @@ impl Pd {
         self.search_paths.clear();
     }
 
-    /// Closes a pd patch for this instance.
+    /// Closes all the patches which are open in this instance.
     ///
     /// # Errors
     ///
@@ -811,15 +971,42 @@ impl Pd {
     ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
     pub fn close_patch(&mut self) -> Result<(), PdError> {
         let _guard = self.set_as_active_instance();
-        if let Some(handle) = self.running_patch.take() {
-            functions::close_patch(handle)?;
+        let mut result = Ok(());
+        // Close all of them even if one fails, reporting the first failure.
+        while let Some((_, patch)) = self.open_patches.pop_last() {
+            if let Err(err) = patch.close() {
+                result = result.and(Err(err));
+            }
         }
-        self.temporary_evaluated_patch.take();
+        result.map_err(Into::into)
+    }
+
+    /// Closes the patch with the given id in this instance.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchLifeCycleError`]
+    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
+    pub fn close_patch_by_id(&mut self, id: PatchId) -> Result<(), PdError> {
+        let _guard = self.set_as_active_instance();
+        let patch = self
+            .open_patches
+            .remove(&id)
+            .ok_or(PatchLifeCycleError::PatchIsNotOpen)?;
+        patch.close()?;
         Ok(())
     }
 
     /// Opens a pd patch for this instance.
     ///
+    /// Any number of patches can be open at the same time, each of them with their own `$0`.
+    /// Opening a patch doesn't close the ones which are already open.
+    ///
+    /// A patch opens even if some of its objects couldn't be created,
+    /// these are listed by [`failed_objects`](Pd::failed_objects) with the id of the patch.
+    ///
     /// The argument should be an absolute path to the patch file.
     /// Absolute and relative paths are supported.
     /// Relative paths and single file names are tried in executable directory and manifest directory.
@@ -831,23 +1018,152 @@ impl Pd {
     /// use libpd_rs::Pd;
     ///
     /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// assert!(pd.open_patch("tests/patches/sine.pd").is_ok());
+    /// let synth = pd.open_patch("synth.pd").unwrap();
+    /// let effects = pd.open_patch("effects.pd").unwrap();
+    ///
+    /// pd.close_patch_by_id(effects).unwrap();
+    /// assert_eq!(pd.open_patches()[0].id, synth);
     /// ```
     ///
     /// # Errors
     ///
     /// A list of errors that can occur:
     /// - [`PatchLifeCycleError`]
-    ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
     ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
     ///   - [`PathDoesNotExist`](crate::error::PatchLifeCycleError::PathDoesNotExist)
-    pub fn open_patch<T: AsRef<Path>>(&mut self, path: T) -> Result<(), PdError> {
+    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen) if the `$0` of the patch can not be read
+    pub fn open_patch<T: AsRef<Path>>(&mut self, path: T) -> Result<PatchId, PdError> {
         let _guard = self.set_as_active_instance();
-        if self.running_patch.is_some() {
-            self.close_patch()?;
+        let (handle, output) = functions::capture_output(|| functions::open_patch(path.as_ref()));
+        self.track_patch(handle?, &output, Some(path.as_ref().to_path_buf()), None)
+    }
+
+    /// Opens a pd patch like [`open_patch`](Pd::open_patch) and watches its file for changes.
+    ///
+    /// Changes are picked up by [`reload_changed_patches`](Pd::reload_changed_patches),
+    /// which is meant to be called periodically, e.g. in the loop which drains the message queues.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let synth = pd.watch_patch("synth.pd").unwrap();
+    ///
+    /// loop {
+    ///     for reload in pd.reload_changed_patches() {
+    ///         match reload {
+    ///             Ok(reload) => {
+    ///                 assert_eq!(reload.id, synth);
+    ///                 println!("synth.pd is reloaded, its $0 is {}", reload.dollar_zero);
+    ///             }
+    ///             Err(err) => eprintln!("{err}"),
+    ///         }
+    ///     }
+    ///     std::thread::sleep(std::time::Duration::from_millis(250));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`open_patch`](Pd::open_patch).
+    pub fn watch_patch<T: AsRef<Path>>(&mut self, path: T) -> Result<PatchId, PdError> {
+        let id = self.open_patch(path.as_ref())?;
+        let file = locate_patch(path.as_ref());
+        if let Some(patch) = self.open_patches.get_mut(&id) {
+            patch.watch = Some(PatchWatch {
+                modified: modified_time(&file),
+                file,
+            });
         }
-        self.running_patch = Some(functions::open_patch(path)?);
-        Ok(())
+        Ok(id)
+    }
+
+    /// Stops watching the file of a patch, the patch stays open.
+    pub fn unwatch_patch(&mut self, id: PatchId) {
+        if let Some(patch) = self.open_patches.get_mut(&id) {
+            patch.watch = None;
+        }
+    }
+
+    /// Reopens the watched patches whose files changed since they are opened or last reloaded.
+    ///
+    /// The file is opened again before the old patch file handle is closed, the [`PatchId`] of the patch stays the same.
+    /// Subscriptions are not bound to a patch, so they keep receiving from the reloaded one.
+    /// libpd holds its lock while opening and closing patches,
+    /// so this happens between two audio blocks and audio keeps running.
+    ///
+    /// Every changed patch is tried, the result of each of them is returned in the order they are opened.
+    /// Files which can not be read at the moment, e.g. while an editor is saving them, are checked again on the next call.
+    /// A patch which fails to open again keeps running as it is and is tried again on the next call.
+    ///
+    /// The result of a patch which fails to reload is a
+    /// [`FailedToReloadPatch`](crate::error::PatchLifeCycleError::FailedToReloadPatch) error
+    /// with the error which occurred, see [`open_patch`](Pd::open_patch).
+    pub fn reload_changed_patches(&mut self) -> Vec<Result<PatchReload, PdError>> {
+        let _guard = self.set_as_active_instance();
+        let changed: Vec<(PatchId, SystemTime)> = self
+            .open_patches
+            .iter()
+            .filter_map(|(id, patch)| {
+                let watch = patch.watch.as_ref()?;
+                let modified = modified_time(&watch.file)?;
+                (watch.modified != Some(modified)).then_some((*id, modified))
+            })
+            .collect();
+        changed
+            .into_iter()
+            .map(|(id, modified)| {
+                self.reload_patch(id, modified).map_err(|err| {
+                    PatchLifeCycleError::FailedToReloadPatch {
+                        id,
+                        source: Box::new(err),
+                    }
+                    .into()
+                })
+            })
+            .collect()
+    }
+
+    /// Opens the file of a patch again and closes the old one keeping its id.
+    ///
+    /// The old patch is left untouched if the file fails to open,
+    /// the modification time of the file is recorded only after it is opened.
+    fn reload_patch(&mut self, id: PatchId, modified: SystemTime) -> Result<PatchReload, PdError> {
+        let path = self
+            .open_patches
+            .get(&id)
+            .and_then(|patch| patch.path.clone())
+            .ok_or(PatchLifeCycleError::PatchIsNotOpen)?;
+
+        let (handle, output) = functions::capture_output(|| functions::open_patch(&path));
+        let handle = handle?;
+        let dollar_zero = match functions::get_dollar_zero(&handle) {
+            Ok(dollar_zero) => dollar_zero,
+            Err(err) => {
+                functions::close_patch(handle).ok();
+                return Err(err.into());
+            }
+        };
+        let failed_objects = functions::failed_objects_with_errors(&handle, &output);
+
+        let Some(patch) = self.open_patches.get_mut(&id) else {
+            functions::close_patch(handle).ok();
+            return Err(PatchLifeCycleError::PatchIsNotOpen.into());
+        };
+        let previous_handle = mem::replace(&mut patch.handle, handle);
+        let previous_dollar_zero = mem::replace(&mut patch.dollar_zero, dollar_zero);
+        patch.failed_objects = failed_objects;
+        if let Some(watch) = patch.watch.as_mut() {
+            watch.modified = Some(modified);
+        }
+        functions::close_patch(previous_handle)?;
+
+        Ok(PatchReload {
+            id,
+            previous_dollar_zero,
+            dollar_zero,
+        })
     }
 
     /// Evaluate a string as a pd patch for this instance.
@@ -855,7 +1171,10 @@ impl Pd {
     /// This function creates a temporary file with the contents passed behind the scenes.
     /// and saves it into the [`Pd`] struct holding onto it until the patch is closed or the instantiated [`Pd`] is dropped.
     ///
-    /// Note: The patch opened after this evaluation could be closed safely with [`close_patch`](Pd::close_patch).
+    /// Like [`open_patch`](Pd::open_patch), the patches which are already open are left open
+    /// and the objects which couldn't be created are listed by [`failed_objects`](Pd::failed_objects).
+    ///
+    /// Note: The patch opened after this evaluation could be closed safely with [`close_patch_by_id`](Pd::close_patch_by_id) or [`close_patch`](Pd::close_patch).
     ///
     /// # Examples
     /// ```rust
@@ -883,14 +1202,11 @@ impl Pd {
     /// A list of errors that can occur:
     /// - [`PatchLifeCycleError`]
     ///   - [`FailedToEvaluateAsPatch`](crate::error::PatchLifeCycleError::FailedToEvaluateAsPatch)
-    ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
     ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
     ///   - [`PathDoesNotExist`](crate::error::PatchLifeCycleError::PathDoesNotExist)
-    pub fn eval_patch<T: AsRef<str>>(&mut self, contents: T) -> Result<(), PdError> {
+    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen) if the `$0` of the patch can not be read
+    pub fn eval_patch<T: AsRef<str>>(&mut self, contents: T) -> Result<PatchId, PdError> {
         let _guard = self.set_as_active_instance();
-        if self.running_patch.is_some() {
-            self.close_patch()?;
-        }
         let temp_file =
             NamedTempFile::new().map_err(|err| PatchLifeCycleError::FailedToEvaluateAsPatch {
                 content: contents.as_ref().to_owned(),
@@ -902,9 +1218,164 @@ impl Pd {
                 msg: err.to_string(),
             }
         })?;
-        self.running_patch = Some(functions::open_patch(temp_file.path())?);
-        self.temporary_evaluated_patch = Some(temp_file);
-        Ok(())
+        let (handle, output) =
+            functions::capture_output(|| functions::open_patch(temp_file.path()));
+        self.track_patch(handle?, &output, None, Some(temp_file))
+    }
+
+    /// Opens a patch from a [`PatchBundle`] for this instance.
+    ///
+    /// pd resolves abstractions and reads files only from the file system,
+    /// so the files of the bundle are written to a private temporary directory
+    /// which is added to the search paths of the instance while the patch is open.
+    /// Abstractions and other files of the bundle, e.g. samples or texts, are found by their paths
+    /// relative to the patch which uses them or to the root of the bundle.
+    ///
+    /// When the patch is closed its directory is removed from the search paths, the other search paths are kept,
+    /// and deleted from the disk.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libpd_rs::{patch::PatchBundle, Pd};
+    ///
+    /// let bundle = PatchBundle::new()
+    ///     .with_file("main.pd", "#N canvas 0 50 450 300 12;\n#X obj 20 20 voice 440;\n")
+    ///     .with_file("voice.pd", "#N canvas 0 50 450 300 12;\n#X obj 20 20 osc~ \\$1;\n");
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let main = pd.open_bundle(&bundle, "main.pd").unwrap();
+    /// pd.close_patch_by_id(main).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchLifeCycleError`]
+    ///   - [`FileNotInBundle`](crate::error::PatchLifeCycleError::FileNotInBundle)
+    ///   - [`InvalidPathInBundle`](crate::error::PatchLifeCycleError::InvalidPathInBundle)
+    ///   - [`FailedToWriteBundle`](crate::error::PatchLifeCycleError::FailedToWriteBundle)
+    ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
+    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen) if the `$0` of the patch can not be read
+    /// - [`IoError`](crate::error::IoError)
+    ///   - [`StringConversion`](crate::error::IoError::StringConversion)
+    pub fn open_bundle<T: AsRef<Path>>(
+        &mut self,
+        bundle: &PatchBundle,
+        entry: T,
+    ) -> Result<PatchId, PdError> {
+        let _guard = self.set_as_active_instance();
+        let entry = entry.as_ref();
+        if !bundle.contains(entry) {
+            return Err(
+                PatchLifeCycleError::FileNotInBundle(entry.to_string_lossy().to_string()).into(),
+            );
+        }
+        bundle.check_paths()?;
+
+        let directory = tempfile::Builder::new()
+            .prefix("libpd-rs-bundle")
+            .tempdir()
+            .map_err(|err| PatchLifeCycleError::FailedToWriteBundle {
+                path: entry.to_string_lossy().to_string(),
+                msg: err.to_string(),
+            })?;
+        bundle.write_to(directory.path())?;
+
+        functions::add_to_search_paths(directory.path())?;
+        let (handle, output) =
+            functions::capture_output(|| functions::open_patch(directory.path().join(entry)));
+        let opened = handle
+            .map_err(PdError::from)
+            .and_then(|handle| self.track_patch(handle, &output, None, None));
+        let id = match opened {
+            Ok(id) => id,
+            Err(err) => {
+                functions::remove_from_search_paths(directory.path());
+                return Err(err);
+            }
+        };
+        if let Some(patch) = self.open_patches.get_mut(&id) {
+            patch.bundle_directory = Some(directory);
+        }
+        Ok(id)
+    }
+
+    /// Lists the patches which are open in this instance in the order they are opened.
+    pub fn open_patches(&self) -> Vec<PatchInfo> {
+        self.open_patches
+            .iter()
+            .map(|(id, patch)| PatchInfo {
+                id: *id,
+                dollar_zero: patch.dollar_zero,
+                path: patch.path.clone(),
+                failed_objects: patch.failed_objects.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the objects pd couldn't create while the patch is opened or last reloaded.
+    ///
+    /// A patch opens even if some of its objects couldn't be created, e.g. for a typo or a missing external,
+    /// pd prints an error for each of them and leaves them as empty boxes.
+    /// What pd printed for an object while the patch is opened is kept in its [`errors`](FailedObject::errors).
+    ///
+    /// Returns `None` if the patch is not open.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let synth = pd.open_patch("synth.pd").unwrap();
+    /// for object in pd.failed_objects(synth).unwrap_or_default() {
+    ///     eprintln!("[{}] at {}, {}: {}", object.text, object.x, object.y, object.errors.join(" "));
+    /// }
+    /// ```
+    pub fn failed_objects(&self, id: PatchId) -> Option<&[FailedObject]> {
+        self.open_patches
+            .get(&id)
+            .map(|patch| patch.failed_objects.as_slice())
+    }
+
+    /// Checks if the patch with the given id is open in this instance.
+    pub fn is_patch_open(&self, id: PatchId) -> bool {
+        self.open_patches.contains_key(&id)
+    }
+
+    /// Stores a freshly opened patch and returns its id.
+    ///
+    /// The output pd printed while opening the patch is used for the errors of its failed objects.
+    /// The patch is closed again if its `$0` can not be read.
+    fn track_patch(
+        &mut self,
+        handle: PatchFileHandle,
+        output: &functions::CapturedOutput,
+        path: Option<PathBuf>,
+        temporary_file: Option<NamedTempFile>,
+    ) -> Result<PatchId, PdError> {
+        let dollar_zero = match functions::get_dollar_zero(&handle) {
+            Ok(dollar_zero) => dollar_zero,
+            Err(err) => {
+                functions::close_patch(handle).ok();
+                return Err(err.into());
+            }
+        };
+        let id = PatchId(self.next_patch_id);
+        self.next_patch_id += 1;
+        let failed_objects = functions::failed_objects_with_errors(&handle, output);
+        self.open_patches.insert(
+            id,
+            OpenPatch {
+                handle,
+                dollar_zero,
+                failed_objects,
+                path,
+                watch: None,
+                bundle_directory: None,
+                _temporary_file: temporary_file,
+            },
+        );
+        Ok(id)
     }
 
     /// Starts listening messages from a source.
@@ -930,10 +1401,12 @@ impl Pd {
         if self.subscriptions.contains_key(source.as_ref()) {
             return Ok(());
         }
-        self.subscriptions.insert(
-            source.as_ref().to_owned(),
-            functions::receive::start_listening_from(source.as_ref())?,
-        );
+        let handle = match self.take_stream_subscription(source.as_ref()) {
+            Some(handle) => handle,
+            None => functions::receive::start_listening_from(source.as_ref())?,
+        };
+        self.subscriptions
+            .insert(source.as_ref().to_owned(), handle);
         Ok(())
     }
 
@@ -961,10 +1434,12 @@ impl Pd {
             if self.subscriptions.contains_key(source.as_ref()) {
                 continue;
             }
-            self.subscriptions.insert(
-                source.as_ref().to_owned(),
-                functions::receive::start_listening_from(source.as_ref())?,
-            );
+            let handle = match self.take_stream_subscription(source.as_ref()) {
+                Some(handle) => handle,
+                None => functions::receive::start_listening_from(source.as_ref())?,
+            };
+            self.subscriptions
+                .insert(source.as_ref().to_owned(), handle);
         }
         Ok(())
     }
@@ -1030,7 +1505,7 @@ impl Pd {
         }
     }
 
-    /// Gets the `$0` of the running patch.
+    /// Gets the `$0` of the most recently opened patch which is still open.
     ///
     /// `$0` id in pd could be thought as a auto generated unique identifier for the patch.
     ///
@@ -1040,12 +1515,24 @@ impl Pd {
     /// - [`PatchLifeCycleError`]
     ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
     pub fn dollar_zero(&mut self) -> Result<i32, PdError> {
-        let _guard = self.set_as_active_instance();
-        if let Some(ref patch) = self.running_patch {
-            let dollar_zero = functions::get_dollar_zero(patch)?;
-            return Ok(dollar_zero);
-        }
-        Err(PatchLifeCycleError::PatchIsNotOpen.into())
+        self.open_patches
+            .last_key_value()
+            .map(|(_, patch)| patch.dollar_zero)
+            .ok_or_else(|| PatchLifeCycleError::PatchIsNotOpen.into())
+    }
+
+    /// Gets the `$0` of the patch with the given id.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchLifeCycleError`]
+    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    pub fn dollar_zero_of(&self, id: PatchId) -> Result<i32, PdError> {
+        self.open_patches
+            .get(&id)
+            .map(|patch| patch.dollar_zero)
+            .ok_or_else(|| PatchLifeCycleError::PatchIsNotOpen.into())
     }
 
     /// Checks if the audio is active.
@@ -1343,6 +1830,67 @@ impl Pd {
         functions::send::send_message_to(receiver, message, list)
     }
 
+    /// Sends a typed MIDI message to the MIDI input objects of this instance.
+    ///
+    /// A [`NoteOff`](crate::midi::MidiMessage::NoteOff) is sent as a note on with the velocity of `0` since pd has no note off.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpd_rs::{midi::MidiMessage, Pd};
+    ///
+    /// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// pd.send_midi(MidiMessage::NoteOn {
+    ///     channel: 0,
+    ///     pitch: 60,
+    ///     velocity: 100,
+    /// })
+    /// .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InvalidMidi`](crate::error::SendError::InvalidMidi)
+    /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
+    pub fn send_midi(&self, message: MidiMessage) -> Result<(), SendError> {
+        message.validate()?;
+
+        let _guard = self.set_as_active_instance();
+        match message {
+            MidiMessage::NoteOff { channel, pitch, .. } => {
+                functions::send::send_note_on(channel.into(), pitch.into(), 0)
+            }
+            MidiMessage::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            } => functions::send::send_note_on(channel.into(), pitch.into(), velocity.into()),
+            MidiMessage::ControlChange {
+                channel,
+                controller,
+                value,
+            } => functions::send::send_control_change(
+                channel.into(),
+                controller.into(),
+                value.into(),
+            ),
+            MidiMessage::ProgramChange { channel, program } => {
+                functions::send::send_program_change(channel.into(), program.into())
+            }
+            MidiMessage::PitchBend { channel, value } => {
+                functions::send::send_pitch_bend(channel.into(), value.into())
+            }
+            MidiMessage::AfterTouch { channel, value } => {
+                functions::send::send_after_touch(channel.into(), value.into())
+            }
+            MidiMessage::PolyAfterTouch {
+                channel,
+                pitch,
+                value,
+            } => functions::send::send_poly_after_touch(channel.into(), pitch.into(), value.into()),
+        }
+    }
+
     /// Calls [`send_note_on`](crate::functions::send::send_note_on) for this instance.
     ///
     /// # Errors
@@ -1466,134 +2014,175 @@ impl Pd {
 
     /// Instance-safe version of [`on_print`](crate::functions::receive::on_print) which doesn't leak memory.
     ///
+    /// The handler is only called for messages printed by this instance.
+    ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_print<'a, F: FnMut(&str) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_print<F: FnMut(&str) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(
-            move |out: *const os::raw::c_char| {
-                callback(str_from_ptr(out));
-            },
-            ClosureMut1::new,
-        );
-
-        let code = callback.code_ptr() as PrintHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_printhook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_printhook(Some(libpd_sys::libpd_print_concatenator));
-        };
-
-        // Always concatenate
-        unsafe {
-            libpd_sys::libpd_set_concatenated_printhook(ptr);
-        }
+        self.callbacks.update(|handlers| {
+            handlers.print = Some(Box::new(callback));
+        });
 
         Ok(())
     }
 
-    /// Instance-safe version of [`on_bang`](crate::functions::receive::on_bang) which doesn't leak memory.
+    /// Registers a handler which is called with every line this instance prints, classified as a [`PrintRecord`](crate::print::PrintRecord).
+    ///
+    /// Lines are assembled from the fragments pd prints, empty lines are skipped.
+    /// It could be registered along with [`on_print`](Pd::on_print) which receives the lines as they are.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpd_rs::{print::PrintLevel, Pd};
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// pd.set_print_prefixes(&["synth"]);
+    /// pd.on_print_record(|record| {
+    ///     if record.level <= PrintLevel::Error {
+    ///         eprintln!("pd {}: {}", record.instance, record.text);
+    ///     }
+    /// })
+    /// .unwrap();
+    /// ```
     ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_bang<'a, F: FnMut(&str) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_print_record<F: FnMut(&PrintRecord) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
-        let _guard = self.set_as_active_instance();
+        self.callbacks.update(|handlers| {
+            handlers.print_record = Some(Box::new(callback));
+        });
 
-        let callback = self.callbacks.add_callback(
-            move |source: *const os::raw::c_char| {
-                let source = unsafe { CStr::from_ptr(source).to_str().expect(C_STR_FAILURE) };
-                callback(source);
-            },
-            ClosureMut1::new,
-        );
+        Ok(())
+    }
 
-        let code = callback.code_ptr() as BangHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_banghook>() };
+    /// Sets the prefixes of the `[print]` objects whose lines are attributed to them in [`PrintRecord`](crate::print::PrintRecord)s.
+    ///
+    /// `print` is always recognized since it is the prefix of `[print]` without arguments.
+    pub fn set_print_prefixes<T: AsRef<str>>(&mut self, prefixes: &[T]) {
+        let prefixes = prefixes
+            .iter()
+            .map(|prefix| prefix.as_ref().to_owned())
+            .collect();
+        self.callbacks.update(|handlers| {
+            handlers.print_prefixes = prefixes;
+        });
+    }
 
-        unsafe {
-            libpd_sys::libpd_set_queued_banghook(ptr);
-        }
+    /// Forwards every line this instance prints to the `log` crate, see [`PrintRecord::log`](crate::print::PrintRecord::log).
+    ///
+    /// This replaces the handler registered with [`on_print_record`](Pd::on_print_record).
+    ///
+    /// # Errors
+    /// - See [`on_print_record`](Pd::on_print_record).
+    #[cfg(feature = "log")]
+    pub fn log_prints(&mut self) -> Result<(), RecieveError> {
+        self.on_print_record(PrintRecord::log)
+    }
 
-        Ok(())
+    /// Forwards every line this instance prints to the `tracing` crate, see [`PrintRecord::trace`](crate::print::PrintRecord::trace).
+    ///
+    /// This replaces the handler registered with [`on_print_record`](Pd::on_print_record).
+    ///
+    /// # Errors
+    /// - See [`on_print_record`](Pd::on_print_record).
+    #[cfg(feature = "tracing")]
+    pub fn trace_prints(&mut self) -> Result<(), RecieveError> {
+        self.on_print_record(PrintRecord::trace)
     }
 
-    /// Instance-safe version of [`on_float`](crate::functions::receive::on_float) which doesn't leak memory.
+    /// Instance-safe version of [`on_bang`](crate::functions::receive::on_bang) which doesn't leak memory.
+    ///
+    /// The handler is only called for bangs received by this instance.
     ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_float<'a, F: FnMut(&str, f32) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_bang<F: FnMut(&str) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(
-            move |source: *const os::raw::c_char, float: f32| {
-                let source = unsafe { CStr::from_ptr(source).to_str().expect(C_STR_FAILURE) };
-                callback(source, float);
-            },
-            ClosureMut2::new,
-        );
-
-        let code = callback.code_ptr() as FloatHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_floathook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_floathook(ptr);
-        }
+        self.callbacks.update(|handlers| {
+            handlers.bang = Some(Box::new(callback));
+        });
 
         Ok(())
     }
 
-    /// Instance-safe version of [`on_double`](crate::functions::receive::on_double) which doesn't leak memory.
+    /// Instance-safe version of [`on_float`](crate::functions::receive::on_float) which doesn't leak memory.
+    ///
+    /// The handler is only called for floats received by this instance.
+    ///
+    /// Like in libpd, you may either have [`on_double`](Pd::on_double) registered or [`on_float`](Pd::on_float) registered. **Not both**.
     ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_double<'a, F: FnMut(&str, f64) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_float<F: FnMut(&str, f32) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
+        self.callbacks.update(|handlers| {
+            handlers.float = Some(Box::new(callback));
+            handlers.double = None;
+        });
+
         let _guard = self.set_as_active_instance();
+        unsafe {
+            libpd_sys::libpd_set_queued_floathook(Some(float_trampoline));
+        }
 
-        let callback = self.callbacks.add_callback(
-            move |source: *const os::raw::c_char, double: f64| {
-                callback(str_from_ptr(source), double);
-            },
-            ClosureMut2::new,
-        );
+        Ok(())
+    }
 
-        let code = callback.code_ptr() as DoubleHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_doublehook>() };
+    /// Instance-safe version of [`on_double`](crate::functions::receive::on_double) which doesn't leak memory.
+    ///
+    /// The handler is only called for floats received by this instance.
+    ///
+    /// Like in libpd, you may either have [`on_double`](Pd::on_double) registered or [`on_float`](Pd::on_float) registered. **Not both**.
+    ///
+    /// # Errors
+    /// - [`RecieveError`]
+    ///    - [`DspActive`](crate::error::RecieveError::DspActive)
+    pub fn on_double<F: FnMut(&str, f64) + Send + 'static>(
+        &mut self,
+        callback: F,
+    ) -> Result<(), RecieveError> {
+        if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
+            return Err(RecieveError::DspActive);
+        }
+
+        self.callbacks.update(|handlers| {
+            handlers.double = Some(Box::new(callback));
+            handlers.float = None;
+        });
 
+        let _guard = self.set_as_active_instance();
         unsafe {
-            libpd_sys::libpd_set_queued_doublehook(ptr);
+            libpd_sys::libpd_set_queued_doublehook(Some(double_trampoline));
         }
 
         Ok(())
@@ -1601,209 +2190,311 @@ impl Pd {
 
     /// Instance-safe version of [`on_symbol`](crate::functions::receive::on_symbol) which doesn't leak memory.
     ///
+    /// The handler is only called for symbols received by this instance.
+    ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_symbol<'a, F: FnMut(&str, &str) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_symbol<F: FnMut(&str, &str) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(
-            move |source: *const os::raw::c_char, symbol: *const os::raw::c_char| {
-                callback(str_from_ptr(source), str_from_ptr(symbol));
-            },
-            ClosureMut2::new,
-        );
-
-        let code = callback.code_ptr() as SymbolHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_symbolhook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_symbolhook(ptr);
-        }
+        self.callbacks.update(|handlers| {
+            handlers.symbol = Some(Box::new(callback));
+        });
 
         Ok(())
     }
 
     /// Instance-safe version of [`on_list`](crate::functions::receive::on_list) which doesn't leak memory.
     ///
+    /// The handler is only called for lists received by this instance.
+    ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_list<'a, F: FnMut(&str, &[Atom]) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_list<F: FnMut(&str, &[Atom]) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(
-            move |source: *const os::raw::c_char,
-                  list_length: i32,
-                  atom_list: *mut libpd_sys::t_atom| {
-                callback(
-                    str_from_ptr(source),
-                    &atoms_from_raw(list_length, atom_list),
-                );
-            },
-            ClosureMut3::new,
-        );
-
-        let code = callback.code_ptr() as ListHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_listhook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_listhook(ptr);
-        }
+        self.callbacks.update(|handlers| {
+            handlers.list = Some(Box::new(callback));
+        });
 
         Ok(())
     }
 
     /// Instance-safe version of [`on_message`](crate::functions::receive::on_message) which doesn't leak memory.
     ///
+    /// The handler is only called for typed messages received by this instance.
+    ///
     /// # Errors
     /// - [`RecieveError`]
     ///    - [`DspActive`](crate::error::RecieveError::DspActive)
-    pub fn on_message<'a, F: FnMut(&str, &str, &[Atom]) + 'a>(
-        &'a mut self,
-        mut callback: F,
+    pub fn on_message<F: FnMut(&str, &str, &[Atom]) + Send + 'static>(
+        &mut self,
+        callback: F,
     ) -> Result<(), RecieveError> {
         if GUARD_FROM_CALLBACK_DURING_DSP && self.audio_active {
             return Err(RecieveError::DspActive);
         }
 
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(
-            move |source: *const os::raw::c_char,
-                  message: *const os::raw::c_char,
-                  list_length: i32,
-                  atom_list: *mut libpd_sys::t_atom| {
-                callback(
-                    str_from_ptr(source),
-                    str_from_ptr(message),
-                    &atoms_from_raw(list_length, atom_list),
-                );
-            },
-            ClosureMut4::new,
-        );
-
-        let code = callback.code_ptr() as MessageHookCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_messagehook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_messagehook(ptr);
-        }
+        self.callbacks.update(|handlers| {
+            handlers.message = Some(Box::new(callback));
+        });
 
         Ok(())
     }
 
     /// Instance-safe version of [`on_midi_note_on`](crate::functions::receive::on_midi_note_on) which doesn't leak memory.
-    pub fn on_midi_note_on<'a, F: FnMut(i32, i32, i32) + 'a>(&'a mut self, callback: F) {
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(callback, ClosureMut3::new);
-
-        let code = callback.code_ptr() as MidiNoteOnCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_noteonhook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_noteonhook(ptr);
-        }
+    pub fn on_midi_note_on<F: FnMut(i32, i32, i32) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_note_on = Some(Box::new(callback));
+        });
     }
 
     /// Instance-safe version of [`on_midi_control_change`](crate::functions::receive::on_midi_control_change) which doesn't leak memory.
-    pub fn on_midi_control_change<'a, F: FnMut(i32, i32, i32) + 'a>(&'a mut self, callback: F) {
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(callback, ClosureMut3::new);
-
-        let code = callback.code_ptr() as MidiControlChangeCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_controlchangehook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_controlchangehook(ptr);
-        }
+    pub fn on_midi_control_change<F: FnMut(i32, i32, i32) + Send + 'static>(
+        &mut self,
+        callback: F,
+    ) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_control_change = Some(Box::new(callback));
+        });
     }
 
     /// Instance-safe version of [`on_midi_program_change`](crate::functions::receive::on_midi_program_change) which doesn't leak memory.
-    pub fn on_midi_program_change<'a, F: FnMut(i32, i32) + 'a>(&'a mut self, callback: F) {
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(callback, ClosureMut2::new);
-
-        let code = callback.code_ptr() as MidiProgramChangeCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_programchangehook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_programchangehook(ptr);
-        }
+    pub fn on_midi_program_change<F: FnMut(i32, i32) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_program_change = Some(Box::new(callback));
+        });
     }
 
     /// Instance-safe version of [`on_midi_pitch_bend`](crate::functions::receive::on_midi_pitch_bend) which doesn't leak memory.
-    pub fn on_midi_pitch_bend<'a, F: FnMut(i32, i32) + 'a>(&'a mut self, callback: F) {
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(callback, ClosureMut2::new);
-
-        let code = callback.code_ptr() as MidiPitchBendCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_pitchbendhook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_pitchbendhook(ptr);
-        }
+    pub fn on_midi_pitch_bend<F: FnMut(i32, i32) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_pitch_bend = Some(Box::new(callback));
+        });
     }
 
     /// Instance-safe version of [`on_midi_after_touch`](crate::functions::receive::on_midi_after_touch) which doesn't leak memory.
-    pub fn on_midi_after_touch<'a, F: FnMut(i32, i32) + 'a>(&'a mut self, callback: F) {
-        let _guard = self.set_as_active_instance();
-
-        let callback = self.callbacks.add_callback(callback, ClosureMut2::new);
-
-        let code = callback.code_ptr() as MidiAfterTouchCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_aftertouchhook>() };
-
-        unsafe {
-            libpd_sys::libpd_set_queued_aftertouchhook(ptr);
-        }
+    pub fn on_midi_after_touch<F: FnMut(i32, i32) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_after_touch = Some(Box::new(callback));
+        });
     }
 
     /// Instance-safe version of [`on_midi_poly_after_touch`](crate::functions::receive::on_midi_poly_after_touch) which doesn't leak memory.
-    pub fn on_midi_poly_after_touch<'a, F: FnMut(i32, i32, i32) + 'a>(&'a mut self, callback: F) {
-        let _guard = self.set_as_active_instance();
+    pub fn on_midi_poly_after_touch<F: FnMut(i32, i32, i32) + Send + 'static>(
+        &mut self,
+        callback: F,
+    ) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_poly_after_touch = Some(Box::new(callback));
+        });
+    }
 
-        let callback = self.callbacks.add_callback(callback, ClosureMut3::new);
+    /// Instance-safe version of [`on_midi_byte`](crate::functions::receive::on_midi_byte) which doesn't leak memory.
+    pub fn on_midi_byte<F: FnMut(i32, i32) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.update(|handlers| {
+            handlers.midi_byte = Some(Box::new(callback));
+        });
+    }
 
-        //let callback = ClosureMut3::new(closure);
-        let code = callback.code_ptr() as MidiPolyAfterTouchCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_polyaftertouchhook>() };
+    /// Registers a single handler for every MIDI channel voice message this instance sends out.
+    ///
+    /// The handler is called alongside the `on_midi_*` handlers while draining the queue with
+    /// [`receive_midi_messages_from_pd`](PdAudioContext::receive_midi_messages_from_pd).
+    /// Messages on ports other than the first one can not be represented as [`MidiMessage`] and are skipped.
+    ///
+    /// Note: pd has no note off, a note off arrives as a [`NoteOn`](crate::midi::MidiMessage::NoteOn) with the velocity of `0`.
+    pub fn on_midi<F: FnMut(MidiMessage) + Send + 'static>(&mut self, callback: F) {
+        self.callbacks.update(|handlers| {
+            handlers.midi = Some(Box::new(callback));
+        });
+    }
 
-        unsafe {
-            libpd_sys::libpd_set_queued_polyaftertouchhook(ptr);
-        }
+    /// Returns a channel which receives every message this instance sends out.
+    ///
+    /// Messages are pushed to the channel while the queues are drained with
+    /// [`receive_messages_from_pd`](PdAudioContext::receive_messages_from_pd) and
+    /// [`receive_midi_messages_from_pd`](PdAudioContext::receive_midi_messages_from_pd),
+    /// so the receiving end can live on another thread, polling it from a UI loop for example.
+    ///
+    /// This works alongside the `on_*` methods, a registered handler is called first and then the message is sent.
+    /// Receivers may be requested any number of times, each of them gets a copy of every message.
+    /// A receiver which is dropped is forgotten on the next message.
+    ///
+    /// Messages to receivers are only delivered if they are subscribed with [`subscribe_to`](Pd::subscribe_to).
+    /// Floats are delivered as [`PdMessage::Double`] unless [`on_float`](Pd::on_float) is registered,
+    /// in that case they are delivered as [`PdMessage::Float`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpd_rs::{types::PdMessage, Pd};
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let messages = pd.message_receiver();
+    /// // The subscription is kept in the instance until it is unsubscribed.
+    /// pd.subscribe_to("from_pd").unwrap();
+    ///
+    /// // In the audio thread
+    /// pd.audio_context().receive_messages_from_pd();
+    ///
+    /// // In any other thread
+    /// for message in messages.try_iter() {
+    ///     if let PdMessage::Double { source, value } = message {
+    ///         println!("{source}: {value}");
+    ///     }
+    /// }
+    /// ```
+    pub fn message_receiver(&mut self) -> mpsc::Receiver<PdMessage> {
+        let (sender, receiver) = mpsc::channel();
+        self.callbacks.update(|handlers| {
+            handlers.senders.push(sender);
+        });
+        receiver
     }
 
-    /// Instance-safe version of [`on_midi_poly_after_touch`](crate::functions::receive::on_midi_poly_after_touch) which doesn't leak memory.
-    pub fn on_midi_byte<'a, F: FnMut(i32, i32) + 'a>(&'a mut self, callback: F) {
+    /// Returns an async stream of the messages which are sent to the source, subscribing to it if it is not subscribed yet.
+    ///
+    /// Messages are delivered while the queues are drained with
+    /// [`receive_messages_from_pd`](PdAudioContext::receive_messages_from_pd), usually in the audio callback,
+    /// so no thread needs to poll pd for them.
+    /// Any number of streams could be created for a source, each of them gets a copy of every message.
+    ///
+    /// A source which is subscribed only for streams is not listed in [`subscriptions`](Pd::subscriptions),
+    /// it is unsubscribed the next time the queues are drained after all of its streams are dropped.
+    /// Subscribing to it with [`subscribe_to`](Pd::subscribe_to) keeps it subscribed after its streams are gone.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// # async fn run() {
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let mut levels = pd.stream_from("level").unwrap();
+    ///
+    /// while let Some(message) = levels.recv().await {
+    ///     println!("{:?}", message.atoms);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`subscribe_to`](Pd::subscribe_to).
+    #[cfg(feature = "async")]
+    pub fn stream_from<T: AsRef<str>>(&mut self, source: T) -> Result<PdStream, PdError> {
+        let source = source.as_ref();
         let _guard = self.set_as_active_instance();
+        let subscribed = self.subscriptions.contains_key(source);
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut result = Ok(());
+        // Binding and adding the stream under the same lock keeps a drain from releasing the source in between.
+        self.callbacks.update(|handlers| {
+            if !subscribed && !handlers.stream_subscriptions.contains_key(source) {
+                match functions::receive::start_listening_from(source) {
+                    Ok(handle) => {
+                        handlers
+                            .stream_subscriptions
+                            .insert(source.to_owned(), handle.into_inner() as usize);
+                    }
+                    Err(error) => {
+                        result = Err(error);
+                        return;
+                    }
+                }
+            }
+            handlers.streams.push((source.to_owned(), sender));
+        });
+        result?;
+        Ok(PdStream::new(source.to_owned(), receiver))
+    }
 
-        let callback = self.callbacks.add_callback(callback, ClosureMut2::new);
-
-        let code = callback.code_ptr() as MidiByteCodePtr<'a>;
-        let ptr = unsafe { *code.cast::<t_libpd_midibytehook>() };
+    /// Sends a message to the receiver and waits for the first message which is sent to the reply source.
+    ///
+    /// A list which starts with a symbol is sent as a message with that selector, a list which starts with a float as a list
+    /// and an empty list as a bang, with [`send_message_to`](Pd::send_message_to).
+    /// The reply source is subscribed before the message is sent, so a reply which is sent right away is not missed.
+    ///
+    /// The message is sent when this function is called, the returned future only waits for the reply.
+    /// The returned [`Request`] doesn't borrow the instance, so it could be spawned on a tokio runtime
+    /// which has the time driver enabled.
+    /// Replies are delivered while the queues are drained, see [`stream_from`](Pd::stream_from).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use libpd_rs::{Atom, Pd};
+    ///
+    /// # async fn run() {
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// pd.open_patch("synth.pd").unwrap();
+    ///
+    /// let reply = pd
+    ///     .request("query", &[Atom::from("voices")], "query_reply", Duration::from_secs(1))
+    ///     .await
+    ///     .unwrap();
+    /// println!("{:?}", reply.atoms);
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`RequestError`]
+    ///   - [`Timeout`](crate::error::RequestError::Timeout)
+    ///   - [`Closed`](crate::error::RequestError::Closed)
+    ///   - [`Pd`](crate::error::RequestError::Pd)
+    #[cfg(feature = "async")]
+    pub fn request<R: AsRef<str>, S: AsRef<str>>(
+        &mut self,
+        receiver: R,
+        atoms: &[Atom],
+        reply_source: S,
+        timeout: core::time::Duration,
+    ) -> Request {
+        let stream = self.stream_from(reply_source).and_then(|stream| {
+            let (selector, list) = match atoms.split_first() {
+                Some((Atom::Symbol(selector), list)) => (selector.as_str(), list),
+                Some(_) => ("list", atoms),
+                None => ("bang", atoms),
+            };
+            self.send_message_to(receiver.as_ref(), selector, list)?;
+            Ok(stream)
+        });
+        Request::new(stream.map_err(RequestError::from), timeout)
+    }
+
+    /// Takes the subscription of a source which is subscribed only for streams, to keep it after its streams are gone.
+    #[cfg(feature = "async")]
+    fn take_stream_subscription(&self, source: &str) -> Option<ReceiverHandle> {
+        let mut handle = None;
+        self.callbacks.update(|handlers| {
+            handle = handlers
+                .stream_subscriptions
+                .remove(source)
+                .map(|handle| ReceiverHandle::from(handle as *mut os::raw::c_void));
+        });
+        handle
+    }
 
-        unsafe {
-            libpd_sys::libpd_set_queued_midibytehook(ptr);
-        }
+    #[cfg(not(feature = "async"))]
+    #[expect(
+        clippy::unused_self,
+        reason = "Sources are only subscribed for streams with the async feature."
+    )]
+    const fn take_stream_subscription(&self, _source: &str) -> Option<ReceiverHandle> {
+        None
     }
 }
 
@@ -1812,9 +2503,16 @@ impl Pd {
 /// Since the instances are thread local, this is just a convenience struct to ensure that the instance is set as the current one before calling any functions.
 ///
 /// If you don't set at least one instance as the current one, the functions in the library will panic.
+///
+/// It also keeps the scratch space which is used to interleave planar buffers,
+/// see [`process_planar_float`](PdAudioContext::process_planar_float).
 #[derive(Debug, Clone)]
 pub struct PdAudioContext {
     instance: PdInstance,
+    input_channels: usize,
+    output_channels: usize,
+    float_scratch: PlanarScratch<f32>,
+    double_scratch: PlanarScratch<f64>,
 }
 
 impl PdAudioContext {
@@ -1822,6 +2520,8 @@ impl PdAudioContext {
     pub fn receive_messages_from_pd(&self) {
         self.instance.set_as_current();
         functions::receive::receive_messages_from_pd();
+        #[cfg(feature = "async")]
+        dispatch(InstanceHandlers::release_closed_streams);
     }
 
     /// Sets the instance as the current one and calls [`receive_midi_messages_from_pd`](crate::functions::receive::receive_midi_messages_from_pd).
@@ -1831,40 +2531,374 @@ impl PdAudioContext {
     }
 
     /// Sets the instance as the current one and calls [`process_float`](crate::functions::process::process_float).
+    ///
+    /// The lengths of the buffers are not checked, see [`try_process_float`](PdAudioContext::try_process_float).
     pub fn process_float(&self, ticks: i32, input: &[f32], output: &mut [f32]) {
         self.instance.set_as_current();
         functions::process::process_float(ticks, input, output);
     }
 
     /// Sets the instance as the current one and calls [`process_double`](crate::functions::process::process_double).
+    ///
+    /// The lengths of the buffers are not checked, see [`try_process_double`](PdAudioContext::try_process_double).
     pub fn process_double(&self, ticks: i32, input: &[f64], output: &mut [f64]) {
         self.instance.set_as_current();
         functions::process::process_double(ticks, input, output);
     }
 
     /// Sets the instance as the current one and calls [`process_short`](crate::functions::process::process_short).
+    ///
+    /// The lengths of the buffers are not checked, see [`try_process_short`](PdAudioContext::try_process_short).
     pub fn process_short(&self, ticks: i32, input: &[i16], output: &mut [i16]) {
         self.instance.set_as_current();
         functions::process::process_short(ticks, input, output);
     }
 
     /// Sets the instance as the current one and calls [`process_raw`](crate::functions::process::process_raw).
+    ///
+    /// The lengths of the buffers are not checked, see [`try_process_raw`](PdAudioContext::try_process_raw).
     pub fn process_raw(&self, input: &[f32], output: &mut [f32]) {
         self.instance.set_as_current();
         functions::process::process_raw(input, output);
     }
 
     /// Sets the instance as the current one and calls [`process_raw_short`](crate::functions::process::process_raw_short).
+    ///
+    /// The lengths of the buffers are not checked, see [`try_process_raw_short`](PdAudioContext::try_process_raw_short).
     pub fn process_raw_short(&self, input: &[i16], output: &mut [i16]) {
         self.instance.set_as_current();
         functions::process::process_raw_short(input, output);
     }
 
     /// Sets the instance as the current one and calls [`process_raw_double`](crate::functions::process::process_raw_double).
+    ///
+    /// The lengths of the buffers are not checked, see [`try_process_raw_double`](PdAudioContext::try_process_raw_double).
     pub fn process_raw_double(&self, input: &[f64], output: &mut [f64]) {
         self.instance.set_as_current();
         functions::process::process_raw_double(input, output);
     }
+
+    /// Checks the lengths of the buffers and calls [`process_float`](PdAudioContext::process_float).
+    ///
+    /// Interleaved buffers need to hold exactly `ticks * block_size()` frames for the configured channel counts,
+    /// so an empty input is only valid for an instance without input channels.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`NegativeTicks`](crate::error::ProcessError::NegativeTicks)
+    /// - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    /// - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    pub fn try_process_float(
+        &self,
+        ticks: i32,
+        input: &[f32],
+        output: &mut [f32],
+    ) -> Result<(), ProcessError> {
+        self.check_buffers(ticks, input.len(), output.len())?;
+        self.process_float(ticks, input, output);
+        Ok(())
+    }
+
+    /// Checks the lengths of the buffers and calls [`process_double`](PdAudioContext::process_double).
+    ///
+    /// Interleaved buffers need to hold exactly `ticks * block_size()` frames for the configured channel counts,
+    /// so an empty input is only valid for an instance without input channels.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`NegativeTicks`](crate::error::ProcessError::NegativeTicks)
+    /// - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    /// - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    pub fn try_process_double(
+        &self,
+        ticks: i32,
+        input: &[f64],
+        output: &mut [f64],
+    ) -> Result<(), ProcessError> {
+        self.check_buffers(ticks, input.len(), output.len())?;
+        self.process_double(ticks, input, output);
+        Ok(())
+    }
+
+    /// Checks the lengths of the buffers and calls [`process_short`](PdAudioContext::process_short).
+    ///
+    /// Interleaved buffers need to hold exactly `ticks * block_size()` frames for the configured channel counts,
+    /// so an empty input is only valid for an instance without input channels.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`NegativeTicks`](crate::error::ProcessError::NegativeTicks)
+    /// - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    /// - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    pub fn try_process_short(
+        &self,
+        ticks: i32,
+        input: &[i16],
+        output: &mut [i16],
+    ) -> Result<(), ProcessError> {
+        self.check_buffers(ticks, input.len(), output.len())?;
+        self.process_short(ticks, input, output);
+        Ok(())
+    }
+
+    /// Checks the lengths of the buffers and calls [`process_raw`](PdAudioContext::process_raw).
+    ///
+    /// Non-interleaved buffers need to hold exactly one tick, `block_size()` frames for the configured channel counts.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    /// - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    pub fn try_process_raw(&self, input: &[f32], output: &mut [f32]) -> Result<(), ProcessError> {
+        self.check_buffers(1, input.len(), output.len())?;
+        self.process_raw(input, output);
+        Ok(())
+    }
+
+    /// Checks the lengths of the buffers and calls [`process_raw_short`](PdAudioContext::process_raw_short).
+    ///
+    /// Non-interleaved buffers need to hold exactly one tick, `block_size()` frames for the configured channel counts.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    /// - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    pub fn try_process_raw_short(
+        &self,
+        input: &[i16],
+        output: &mut [i16],
+    ) -> Result<(), ProcessError> {
+        self.check_buffers(1, input.len(), output.len())?;
+        self.process_raw_short(input, output);
+        Ok(())
+    }
+
+    /// Checks the lengths of the buffers and calls [`process_raw_double`](PdAudioContext::process_raw_double).
+    ///
+    /// Non-interleaved buffers need to hold exactly one tick, `block_size()` frames for the configured channel counts.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    /// - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    pub fn try_process_raw_double(
+        &self,
+        input: &[f64],
+        output: &mut [f64],
+    ) -> Result<(), ProcessError> {
+        self.check_buffers(1, input.len(), output.len())?;
+        self.process_raw_double(input, output);
+        Ok(())
+    }
+
+    /// Checks the lengths of buffers which hold the given amount of ticks for the configured channel counts.
+    fn check_buffers(&self, ticks: i32, input: usize, output: usize) -> Result<(), ProcessError> {
+        let ticks = usize::try_from(ticks).map_err(|_| ProcessError::NegativeTicks(ticks))?;
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "The block size of pd is a positive constant."
+        )]
+        let frames = ticks * functions::block_size() as usize;
+
+        let expected = frames * self.input_channels;
+        if input != expected {
+            return Err(ProcessError::InputLengthMismatch {
+                expected,
+                found: input,
+            });
+        }
+        let expected = frames * self.output_channels;
+        if output != expected {
+            return Err(ProcessError::OutputLengthMismatch {
+                expected,
+                found: output,
+            });
+        }
+        Ok(())
+    }
+
+    /// Processes planar buffers, one slice per channel, through [`process_float`](crate::functions::process::process_float).
+    ///
+    /// The channel counts need to match the ones the instance is configured with,
+    /// every channel needs to have the same length which is a multiple of the block size.
+    /// An instance without input channels takes an empty slice of inputs.
+    ///
+    /// The channels are interleaved in a scratch space which is kept in the context.
+    /// It is allocated for buffers up to 16 ticks, 1024 frames with the default block size, when the context is created.
+    /// A larger buffer grows it, which allocates in the audio thread,
+    /// call [`reserve_planar`](PdAudioContext::reserve_planar) before starting audio for larger buffers.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let mut ctx = pd.audio_context();
+    /// ctx.reserve_planar(512);
+    ///
+    /// // In the audio callback
+    /// let input = [0.0_f32; 512];
+    /// let (mut left, mut right) = ([0.0_f32; 512], [0.0_f32; 512]);
+    /// ctx.process_planar_float(&[&input], &mut [&mut left, &mut right])
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InputChannelMismatch`](crate::error::ProcessError::InputChannelMismatch)
+    /// - [`OutputChannelMismatch`](crate::error::ProcessError::OutputChannelMismatch)
+    /// - [`UnevenChannelLengths`](crate::error::ProcessError::UnevenChannelLengths)
+    /// - [`PartialBlock`](crate::error::ProcessError::PartialBlock)
+    pub fn process_planar_float(
+        &mut self,
+        inputs: &[&[f32]],
+        outputs: &mut [&mut [f32]],
+    ) -> Result<(), ProcessError> {
+        let ticks = self.planar_ticks(inputs, outputs)?;
+        self.instance.set_as_current();
+        self.float_scratch
+            .process(ticks, inputs, outputs, functions::process::process_float);
+        Ok(())
+    }
+
+    /// Processes planar buffers, one slice per channel, through [`process_double`](crate::functions::process::process_double).
+    ///
+    /// See [`process_planar_float`](PdAudioContext::process_planar_float) for the requirements of the buffers
+    /// and the scratch space, skipping [`reserve_planar`](PdAudioContext::reserve_planar) for buffers larger than 16 ticks
+    /// allocates in the audio thread.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InputChannelMismatch`](crate::error::ProcessError::InputChannelMismatch)
+    /// - [`OutputChannelMismatch`](crate::error::ProcessError::OutputChannelMismatch)
+    /// - [`UnevenChannelLengths`](crate::error::ProcessError::UnevenChannelLengths)
+    /// - [`PartialBlock`](crate::error::ProcessError::PartialBlock)
+    pub fn process_planar_double(
+        &mut self,
+        inputs: &[&[f64]],
+        outputs: &mut [&mut [f64]],
+    ) -> Result<(), ProcessError> {
+        let ticks = self.planar_ticks(inputs, outputs)?;
+        self.instance.set_as_current();
+        self.double_scratch
+            .process(ticks, inputs, outputs, functions::process::process_double);
+        Ok(())
+    }
+
+    /// Allocates the scratch space of planar processing for buffers up to the given amount of frames.
+    ///
+    /// The context is created with space for 16 ticks, this is only needed for larger buffers.
+    pub fn reserve_planar(&mut self, frames: usize) {
+        self.float_scratch
+            .reserve(frames, self.input_channels, self.output_channels);
+        self.double_scratch
+            .reserve(frames, self.input_channels, self.output_channels);
+    }
+
+    /// Validates planar buffers against the configuration of the instance and returns the amount of ticks they hold.
+    fn planar_ticks<T>(&self, inputs: &[&[T]], outputs: &[&mut [T]]) -> Result<i32, ProcessError> {
+        if inputs.len() != self.input_channels {
+            return Err(ProcessError::InputChannelMismatch {
+                expected: self.input_channels,
+                found: inputs.len(),
+            });
+        }
+        if outputs.len() != self.output_channels {
+            return Err(ProcessError::OutputChannelMismatch {
+                expected: self.output_channels,
+                found: outputs.len(),
+            });
+        }
+
+        let mut lengths = inputs
+            .iter()
+            .map(|channel| channel.len())
+            .chain(outputs.iter().map(|channel| channel.len()));
+        let frames = lengths.next().unwrap_or(0);
+        if lengths.any(|length| length != frames) {
+            return Err(ProcessError::UnevenChannelLengths);
+        }
+
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "The block size of pd is a positive constant."
+        )]
+        let block_size = functions::block_size() as usize;
+        let ticks = frames.checked_div(block_size).unwrap_or(0);
+        if ticks * block_size != frames {
+            return Err(ProcessError::PartialBlock { frames, block_size });
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_possible_wrap,
+            reason = "Audio buffers hold far less ticks than i32::MAX."
+        )]
+        let ticks = ticks as i32;
+        Ok(ticks)
+    }
+}
+
+/// The amount of ticks the planar scratch space of a new [`PdAudioContext`] is allocated for.
+const DEFAULT_PLANAR_TICKS: usize = 16;
+
+/// Interleaved buffers which planar buffers are copied into and out of.
+#[derive(Debug, Clone, Default)]
+struct PlanarScratch<T> {
+    input: Vec<T>,
+    output: Vec<T>,
+}
+
+impl<T: Copy + Default> PlanarScratch<T> {
+    fn reserve(&mut self, frames: usize, input_channels: usize, output_channels: usize) {
+        self.input
+            .reserve((frames * input_channels).saturating_sub(self.input.len()));
+        self.output
+            .reserve((frames * output_channels).saturating_sub(self.output.len()));
+    }
+
+    /// Interleaves the inputs, processes them and deinterleaves the result to the outputs.
+    fn process<F: FnOnce(i32, &[T], &mut [T])>(
+        &mut self,
+        ticks: i32,
+        inputs: &[&[T]],
+        outputs: &mut [&mut [T]],
+        process: F,
+    ) {
+        let frames = inputs
+            .first()
+            .map(|channel| channel.len())
+            .or_else(|| outputs.first().map(|channel| channel.len()))
+            .unwrap_or(0);
+
+        self.input.clear();
+        self.input.resize(frames * inputs.len(), T::default());
+        for (index, channel) in inputs.iter().enumerate() {
+            let slots = self.input.iter_mut().skip(index).step_by(inputs.len());
+            for (slot, sample) in slots.zip(channel.iter()) {
+                *slot = *sample;
+            }
+        }
+
+        self.output.clear();
+        self.output.resize(frames * outputs.len(), T::default());
+        process(ticks, &self.input, &mut self.output);
+
+        let channels = outputs.len();
+        for (index, channel) in outputs.iter_mut().enumerate() {
+            let slots = self.output.iter().skip(index).step_by(channels);
+            for (sample, slot) in channel.iter_mut().zip(slots) {
+                *sample = *slot;
+            }
+        }
+    }
 }
 
 /// When an instance is set as the active instance for the thread, this guard is returned.
@@ -1896,63 +2930,431 @@ impl Drop for ActiveInstanceGuard {
     }
 }
 
-// Tracking for ensuring that resources created to handle the callbacks are cleaned up when `Pd` is dropped.
-struct Callbacks {
-    callbacks: Vec<CallbackDtor>,
+type PrintHandler = Box<dyn FnMut(&str) + Send>;
+type PrintRecordHandler = Box<dyn FnMut(&PrintRecord) + Send>;
+type BangHandler = Box<dyn FnMut(&str) + Send>;
+type FloatHandler = Box<dyn FnMut(&str, f32) + Send>;
+type DoubleHandler = Box<dyn FnMut(&str, f64) + Send>;
+type SymbolHandler = Box<dyn FnMut(&str, &str) + Send>;
+type ListHandler = Box<dyn FnMut(&str, &[Atom]) + Send>;
+type MessageHandler = Box<dyn FnMut(&str, &str, &[Atom]) + Send>;
+type MidiTripleHandler = Box<dyn FnMut(i32, i32, i32) + Send>;
+type MidiPairHandler = Box<dyn FnMut(i32, i32) + Send>;
+type MidiHandler = Box<dyn FnMut(MidiMessage) + Send>;
+
+/// The handlers registered by a single [`Pd`] instance.
+#[derive(Default)]
+struct InstanceHandlers {
+    print: Option<PrintHandler>,
+    print_record: Option<PrintRecordHandler>,
+    print_prefixes: Vec<String>,
+    print_lines: LineAssembler,
+    bang: Option<BangHandler>,
+    float: Option<FloatHandler>,
+    double: Option<DoubleHandler>,
+    symbol: Option<SymbolHandler>,
+    list: Option<ListHandler>,
+    message: Option<MessageHandler>,
+    midi_note_on: Option<MidiTripleHandler>,
+    midi_control_change: Option<MidiTripleHandler>,
+    midi_program_change: Option<MidiPairHandler>,
+    midi_pitch_bend: Option<MidiPairHandler>,
+    midi_after_touch: Option<MidiPairHandler>,
+    midi_poly_after_touch: Option<MidiTripleHandler>,
+    midi_byte: Option<MidiPairHandler>,
+    midi: Option<MidiHandler>,
+    senders: Vec<mpsc::Sender<PdMessage>>,
+    #[cfg(feature = "async")]
+    streams: Vec<(String, tokio::sync::mpsc::UnboundedSender<AddressedMessage>)>,
+    /// The handles of the sources which are subscribed only for streams, as addresses to keep the handlers `Send`.
+    #[cfg(feature = "async")]
+    stream_subscriptions: HashMap<String, usize>,
 }
 
-impl Callbacks {
-    const fn new() -> Self {
-        Self {
-            callbacks: Vec::new(),
+impl InstanceHandlers {
+    /// Sends the message to every live receiver, the message is only built if there is any.
+    fn emit<F: FnOnce() -> PdMessage>(&mut self, message: F) {
+        if self.senders.is_empty() && !self.has_streams() {
+            return;
         }
+        let message = message();
+        #[cfg(feature = "async")]
+        self.emit_to_streams(&message);
+        self.senders
+            .retain(|sender| sender.send(message.clone()).is_ok());
     }
 
-    fn add_callback<'a, A: 'a, B, F: Fn(&'a mut A) -> B + 'a>(
-        &mut self,
-        callback: A,
-        closure_mut_fn: F,
-    ) -> &'a mut B {
-        let boxed = Box::new(callback);
-        let raw = Box::into_raw(boxed);
-        let drop = CallbackDtor::drop_inner::<A>;
-
-        self.callbacks.push(CallbackDtor {
-            drop,
-            data: raw.cast(),
-        });
+    #[cfg(feature = "async")]
+    fn has_streams(&self) -> bool {
+        !self.streams.is_empty()
+    }
 
-        let callback = unsafe { raw.as_mut().expect("callback is nullptr") };
+    #[cfg(not(feature = "async"))]
+    const fn has_streams(&self) -> bool {
+        false
+    }
 
-        let closure = Box::new(closure_mut_fn(callback));
-        let raw = Box::into_raw(closure);
-        let drop = CallbackDtor::drop_inner::<B>;
+    /// Sends the message to the live streams of its source, the streams of any source which are dropped are removed.
+    #[cfg(feature = "async")]
+    fn emit_to_streams(&mut self, message: &PdMessage) {
+        self.release_closed_streams();
+        let Some(message) = AddressedMessage::from_pd_message(message) else {
+            return;
+        };
+        self.streams.retain(|(source, sender)| {
+            *source != message.receiver || sender.send(message.clone()).is_ok()
+        });
+    }
 
-        self.callbacks.push(CallbackDtor {
-            drop,
-            data: raw.cast(),
+    /// Removes the dropped streams and unsubscribes the sources which are subscribed only for streams and have none left.
+    ///
+    /// It runs while the queues of the instance are drained, so the instance is the current one.
+    #[cfg(feature = "async")]
+    fn release_closed_streams(&mut self) {
+        let streams = self.streams.len();
+        self.streams.retain(|(_, sender)| !sender.is_closed());
+        if self.streams.len() == streams {
+            return;
+        }
+        let live = &self.streams;
+        self.stream_subscriptions.retain(|source, handle| {
+            let keep = live.iter().any(|(live, _)| live == source);
+            if !keep {
+                functions::receive::stop_listening_from(ReceiverHandle::from(
+                    *handle as *mut os::raw::c_void,
+                ));
+            }
+            keep
         });
+    }
 
-        unsafe { raw.as_mut().expect("callback is nullptr") }
+    /// Calls the typed MIDI handler, messages which are not representable as [`MidiMessage`] are skipped.
+    fn emit_midi<F: FnOnce() -> Option<MidiMessage>>(&mut self, message: F) {
+        let Some(handler) = self.midi.as_mut() else {
+            return;
+        };
+        if let Some(message) = message().filter(|message| message.validate().is_ok()) {
+            handler(message);
+        }
     }
 }
 
-// The smallest amount of information required to clean up an arbitrary Box
-struct CallbackDtor {
-    drop: unsafe fn(*mut ()),
-    data: *mut (),
+/// Handlers of every live [`Pd`], keyed by the address of their `_pdinstance`.
+///
+/// Instance numbers are reassigned when an instance is freed so the address is the only stable key.
+static INSTANCE_HANDLERS: LazyLock<Mutex<HashMap<usize, Arc<Mutex<InstanceHandlers>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Owns the entry of a `Pd` in the dispatch table and removes it when `Pd` is dropped.
+struct Callbacks {
+    instance: usize,
+    handlers: Arc<Mutex<InstanceHandlers>>,
 }
 
-impl Drop for CallbackDtor {
+impl Callbacks {
+    fn register(instance: &PdInstance) -> Self {
+        let key = instance.as_ptr() as usize;
+        let handlers = Arc::new(Mutex::new(InstanceHandlers::default()));
+        INSTANCE_HANDLERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, Arc::clone(&handlers));
+
+        Self {
+            instance: key,
+            handlers,
+        }
+    }
+
+    fn update<F: FnOnce(&mut InstanceHandlers)>(&self, f: F) {
+        f(&mut self.handlers.lock().unwrap_or_else(PoisonError::into_inner));
+    }
+}
+
+impl Drop for Callbacks {
     fn drop(&mut self) {
-        unsafe { (self.drop)(self.data) }
+        let mut table = INSTANCE_HANDLERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if table
+            .get(&self.instance)
+            .is_some_and(|handlers| Arc::ptr_eq(handlers, &self.handlers))
+        {
+            table.remove(&self.instance);
+        }
     }
 }
 
-impl CallbackDtor {
-    fn drop_inner<T>(p: *mut ()) {
-        unsafe { drop(Box::<T>::from_raw(p.cast())) }
+/// Calls `f` with the handlers of the instance which is currently active on this thread.
+///
+/// Hooks are only ever called from `libpd_queued_receive_*` which runs on the current instance.
+fn dispatch<F: FnOnce(&mut InstanceHandlers)>(f: F) {
+    let current = unsafe { libpd_sys::libpd_this_instance() };
+    if current.is_null() {
+        return;
     }
+
+    let handlers = INSTANCE_HANDLERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&(current as usize))
+        .cloned();
+
+    if let Some(handlers) = handlers {
+        f(&mut handlers.lock().unwrap_or_else(PoisonError::into_inner));
+    }
+}
+
+/// Points the hooks of the current instance to the dispatching trampolines.
+fn install_dispatching_hooks() {
+    unsafe {
+        libpd_sys::libpd_set_queued_printhook(Some(libpd_sys::libpd_print_concatenator));
+        libpd_sys::libpd_set_concatenated_printhook(Some(print_trampoline));
+        libpd_sys::libpd_set_queued_banghook(Some(bang_trampoline));
+        libpd_sys::libpd_set_queued_doublehook(Some(double_trampoline));
+        libpd_sys::libpd_set_queued_symbolhook(Some(symbol_trampoline));
+        libpd_sys::libpd_set_queued_listhook(Some(list_trampoline));
+        libpd_sys::libpd_set_queued_messagehook(Some(message_trampoline));
+        libpd_sys::libpd_set_queued_noteonhook(Some(midi_note_on_trampoline));
+        libpd_sys::libpd_set_queued_controlchangehook(Some(midi_control_change_trampoline));
+        libpd_sys::libpd_set_queued_programchangehook(Some(midi_program_change_trampoline));
+        libpd_sys::libpd_set_queued_pitchbendhook(Some(midi_pitch_bend_trampoline));
+        libpd_sys::libpd_set_queued_aftertouchhook(Some(midi_after_touch_trampoline));
+        libpd_sys::libpd_set_queued_polyaftertouchhook(Some(midi_poly_after_touch_trampoline));
+        libpd_sys::libpd_set_queued_midibytehook(Some(midi_byte_trampoline));
+    }
+}
+
+unsafe extern "C" fn print_trampoline(msg: *const os::raw::c_char) {
+    let msg = str_from_ptr(msg);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.print.as_mut() {
+            handler(msg);
+        }
+        let InstanceHandlers {
+            print_record,
+            print_prefixes,
+            print_lines,
+            ..
+        } = handlers;
+        if let Some(handler) = print_record.as_mut() {
+            let instance = unsafe { (*libpd_sys::libpd_this_instance()).pd_instanceno };
+            let mut emit =
+                |line: &str| handler(&PrintRecord::parse(instance, line, print_prefixes));
+            // The concatenating hook strips the newline which ends the line.
+            print_lines.push(msg, &mut emit);
+            print_lines.push("\n", &mut emit);
+        }
+        handlers.emit(|| PdMessage::Print(msg.to_owned()));
+    });
+}
+
+unsafe extern "C" fn bang_trampoline(source: *const os::raw::c_char) {
+    let source = str_from_ptr(source);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.bang.as_mut() {
+            handler(source);
+        }
+        handlers.emit(|| PdMessage::Bang {
+            source: source.to_owned(),
+        });
+    });
+}
+
+unsafe extern "C" fn float_trampoline(source: *const os::raw::c_char, value: f32) {
+    let source = str_from_ptr(source);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.float.as_mut() {
+            handler(source, value);
+        }
+        handlers.emit(|| PdMessage::Float {
+            source: source.to_owned(),
+            value,
+        });
+    });
+}
+
+unsafe extern "C" fn double_trampoline(source: *const os::raw::c_char, value: f64) {
+    let source = str_from_ptr(source);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.double.as_mut() {
+            handler(source, value);
+        }
+        handlers.emit(|| PdMessage::Double {
+            source: source.to_owned(),
+            value,
+        });
+    });
+}
+
+unsafe extern "C" fn symbol_trampoline(
+    source: *const os::raw::c_char,
+    symbol: *const os::raw::c_char,
+) {
+    let source = str_from_ptr(source);
+    let symbol = str_from_ptr(symbol);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.symbol.as_mut() {
+            handler(source, symbol);
+        }
+        handlers.emit(|| PdMessage::Symbol {
+            source: source.to_owned(),
+            symbol: symbol.to_owned(),
+        });
+    });
+}
+
+unsafe extern "C" fn list_trampoline(
+    source: *const os::raw::c_char,
+    list_length: i32,
+    atom_list: *mut libpd_sys::t_atom,
+) {
+    let source = str_from_ptr(source);
+    let atoms = atoms_from_raw(list_length, atom_list);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.list.as_mut() {
+            handler(source, &atoms);
+        }
+        handlers.emit(|| PdMessage::List {
+            source: source.to_owned(),
+            list: atoms,
+        });
+    });
+}
+
+unsafe extern "C" fn message_trampoline(
+    source: *const os::raw::c_char,
+    message: *const os::raw::c_char,
+    list_length: i32,
+    atom_list: *mut libpd_sys::t_atom,
+) {
+    let source = str_from_ptr(source);
+    let message = str_from_ptr(message);
+    let atoms = atoms_from_raw(list_length, atom_list);
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.message.as_mut() {
+            handler(source, message, &atoms);
+        }
+        handlers.emit(|| PdMessage::Message {
+            source: source.to_owned(),
+            selector: message.to_owned(),
+            list: atoms,
+        });
+    });
+}
+
+unsafe extern "C" fn midi_note_on_trampoline(channel: i32, pitch: i32, velocity: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_note_on.as_mut() {
+            handler(channel, pitch, velocity);
+        }
+        handlers.emit(|| PdMessage::MidiNoteOn {
+            channel,
+            pitch,
+            velocity,
+        });
+        handlers.emit_midi(|| {
+            Some(MidiMessage::NoteOn {
+                channel: u8::try_from(channel).ok()?,
+                pitch: u8::try_from(pitch).ok()?,
+                velocity: u8::try_from(velocity).ok()?,
+            })
+        });
+    });
+}
+
+unsafe extern "C" fn midi_control_change_trampoline(channel: i32, controller: i32, value: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_control_change.as_mut() {
+            handler(channel, controller, value);
+        }
+        handlers.emit(|| PdMessage::MidiControlChange {
+            channel,
+            controller,
+            value,
+        });
+        handlers.emit_midi(|| {
+            Some(MidiMessage::ControlChange {
+                channel: u8::try_from(channel).ok()?,
+                controller: u8::try_from(controller).ok()?,
+                value: u8::try_from(value).ok()?,
+            })
+        });
+    });
+}
+
+unsafe extern "C" fn midi_program_change_trampoline(channel: i32, value: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_program_change.as_mut() {
+            handler(channel, value);
+        }
+        handlers.emit(|| PdMessage::MidiProgramChange { channel, value });
+        handlers.emit_midi(|| {
+            Some(MidiMessage::ProgramChange {
+                channel: u8::try_from(channel).ok()?,
+                program: u8::try_from(value).ok()?,
+            })
+        });
+    });
+}
+
+unsafe extern "C" fn midi_pitch_bend_trampoline(channel: i32, value: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_pitch_bend.as_mut() {
+            handler(channel, value);
+        }
+        handlers.emit(|| PdMessage::MidiPitchBend { channel, value });
+        handlers.emit_midi(|| {
+            Some(MidiMessage::PitchBend {
+                channel: u8::try_from(channel).ok()?,
+                value: i16::try_from(value).ok()?,
+            })
+        });
+    });
+}
+
+unsafe extern "C" fn midi_after_touch_trampoline(channel: i32, value: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_after_touch.as_mut() {
+            handler(channel, value);
+        }
+        handlers.emit(|| PdMessage::MidiAfterTouch { channel, value });
+        handlers.emit_midi(|| {
+            Some(MidiMessage::AfterTouch {
+                channel: u8::try_from(channel).ok()?,
+                value: u8::try_from(value).ok()?,
+            })
+        });
+    });
+}
+
+unsafe extern "C" fn midi_poly_after_touch_trampoline(channel: i32, pitch: i32, value: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_poly_after_touch.as_mut() {
+            handler(channel, pitch, value);
+        }
+        handlers.emit(|| PdMessage::MidiPolyAfterTouch {
+            channel,
+            pitch,
+            value,
+        });
+        handlers.emit_midi(|| {
+            Some(MidiMessage::PolyAfterTouch {
+                channel: u8::try_from(channel).ok()?,
+                pitch: u8::try_from(pitch).ok()?,
+                value: u8::try_from(value).ok()?,
+            })
+        });
+    });
+}
+
+unsafe extern "C" fn midi_byte_trampoline(port: i32, byte: i32) {
+    dispatch(|handlers| {
+        if let Some(handler) = handlers.midi_byte.as_mut() {
+            handler(port, byte);
+        }
+        handlers.emit(|| PdMessage::MidiByte { port, byte });
+    });
 }
 
 fn atoms_from_raw(list_length: i32, atom_list: *mut libpd_sys::t_atom) -> Vec<Atom> {
@@ -1964,10 +3366,60 @@ fn atoms_from_raw(list_length: i32, atom_list: *mut libpd_sys::t_atom) -> Vec<At
     make_atom_list_from_t_atom_list(atom_list)
 }
 
-fn str_from_ptr<'a>(s: *const i8) -> &'a str {
+fn str_from_ptr<'a>(s: *const os::raw::c_char) -> &'a str {
     unsafe { CStr::from_ptr(s).to_str().expect(C_STR_FAILURE) }
 }
 
+/// A patch which is open in a [`Pd`] instance.
+struct OpenPatch {
+    handle: PatchFileHandle,
+    dollar_zero: i32,
+    failed_objects: Vec<FailedObject>,
+    path: Option<PathBuf>,
+    /// Set for the patches which are opened with [`Pd::watch_patch`].
+    watch: Option<PatchWatch>,
+    /// Patches opened from a bundle are backed by a temporary directory which is in the search paths while the patch is open.
+    bundle_directory: Option<TempDir>,
+    /// Evaluated patches are backed by a temporary file which is removed when the patch is closed.
+    _temporary_file: Option<NamedTempFile>,
+}
+
+impl OpenPatch {
+    /// Closes the patch, the directory of a bundle is removed from the search paths and then from the disk.
+    fn close(self) -> Result<(), PatchLifeCycleError> {
+        let result = functions::close_patch(self.handle);
+        if let Some(directory) = &self.bundle_directory {
+            functions::remove_from_search_paths(directory.path());
+        }
+        result
+    }
+}
+
+/// The file of a watched patch and its modification time when it is last opened.
+struct PatchWatch {
+    file: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Finds the file of a patch the way [`functions::open_patch`] does,
+/// relative paths are tried in the executable directory and then in the manifest directory.
+fn locate_patch(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    env::current_exe()
+        .ok()
+        .and_then(|executable| executable.parent().map(|directory| directory.join(path)))
+        .filter(|candidate| candidate.exists())
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path))
+}
+
+fn modified_time(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
 /// Type to assist with checking for the validity of sending a message.
 struct SentMessageInfo {
     capacity: i32,
@@ -0,0 +1,442 @@
+use crate::error::MidiError;
+
+/// The highest channel a [`MidiMessage`] can address.
+pub const MAX_CHANNEL: u8 = 15;
+/// The highest value of a 7-bit MIDI data byte.
+pub const MAX_DATA: u8 = 127;
+/// The lowest pitch bend value, pd and libpd use a signed range for pitch bend.
+pub const MIN_PITCH_BEND: i16 = -8192;
+/// The highest pitch bend value, pd and libpd use a signed range for pitch bend.
+pub const MAX_PITCH_BEND: i16 = 8191;
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const POLY_AFTER_TOUCH: u8 = 0xA0;
+const CONTROL_CHANGE: u8 = 0xB0;
+const PROGRAM_CHANGE: u8 = 0xC0;
+const AFTER_TOUCH: u8 = 0xD0;
+const PITCH_BEND: u8 = 0xE0;
+
+/// A MIDI channel voice message.
+///
+/// Channels are zero indexed and in the range of `0-15`, data bytes are in the range of `0-127`
+/// and pitch bend is in the signed range of `-8192..=8191` as in pd.
+///
+/// libpd encodes MIDI ports in the channel number (`libpd_channel = pd_channel + 16 * pd_port`),
+/// this type only addresses the first port. Use the functions in [`functions::send`](crate::functions::send)
+/// and [`functions::receive`](crate::functions::receive) to work with other ports.
+///
+/// Fields are public so the values are validated when the message is used,
+/// call [`validate`](MidiMessage::validate) to check them upfront.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MidiMessage {
+    /// A note off, sent to pd as a note on with the velocity of `0` since pd has no note off.
+    NoteOff {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The pitch, `0-127`.
+        pitch: u8,
+        /// The release velocity, `0-127`.
+        velocity: u8,
+    },
+    /// A note on.
+    NoteOn {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The pitch, `0-127`.
+        pitch: u8,
+        /// The velocity, `0-127`.
+        velocity: u8,
+    },
+    /// A control change.
+    ControlChange {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The controller number, `0-127`.
+        controller: u8,
+        /// The value, `0-127`.
+        value: u8,
+    },
+    /// A program change.
+    ProgramChange {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The program, `0-127`.
+        program: u8,
+    },
+    /// A pitch bend.
+    PitchBend {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The amount of bend, `-8192..=8191`.
+        value: i16,
+    },
+    /// A channel pressure.
+    AfterTouch {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The pressure, `0-127`.
+        value: u8,
+    },
+    /// A key pressure.
+    PolyAfterTouch {
+        /// The channel, `0-15`.
+        channel: u8,
+        /// The pitch, `0-127`.
+        pitch: u8,
+        /// The pressure, `0-127`.
+        value: u8,
+    },
+}
+
+impl MidiMessage {
+    /// Returns the channel of the message.
+    pub const fn channel(&self) -> u8 {
+        match *self {
+            Self::NoteOff { channel, .. }
+            | Self::NoteOn { channel, .. }
+            | Self::ControlChange { channel, .. }
+            | Self::ProgramChange { channel, .. }
+            | Self::PitchBend { channel, .. }
+            | Self::AfterTouch { channel, .. }
+            | Self::PolyAfterTouch { channel, .. } => channel,
+        }
+    }
+
+    /// Checks that the channel, the data bytes and the pitch bend value are in range.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`ChannelOutOfRange`](crate::error::MidiError::ChannelOutOfRange)
+    /// - [`DataOutOfRange`](crate::error::MidiError::DataOutOfRange)
+    /// - [`PitchBendOutOfRange`](crate::error::MidiError::PitchBendOutOfRange)
+    pub fn validate(&self) -> Result<(), MidiError> {
+        let channel = self.channel();
+        if channel > MAX_CHANNEL {
+            return Err(MidiError::ChannelOutOfRange(channel));
+        }
+
+        // Missing data bytes are filled with zero which is always in range.
+        let data = match *self {
+            Self::NoteOff {
+                pitch, velocity, ..
+            }
+            | Self::NoteOn {
+                pitch, velocity, ..
+            } => [pitch, velocity],
+            Self::ControlChange {
+                controller, value, ..
+            } => [controller, value],
+            Self::ProgramChange { program, .. } => [program, 0],
+            Self::AfterTouch { value, .. } => [value, 0],
+            Self::PolyAfterTouch { pitch, value, .. } => [pitch, value],
+            Self::PitchBend { value, .. } => {
+                if !(MIN_PITCH_BEND..=MAX_PITCH_BEND).contains(&value) {
+                    return Err(MidiError::PitchBendOutOfRange(value));
+                }
+                [0, 0]
+            }
+        };
+
+        match data.into_iter().find(|byte| *byte > MAX_DATA) {
+            Some(byte) => Err(MidiError::DataOutOfRange(byte)),
+            None => Ok(()),
+        }
+    }
+
+    /// Encodes the message as raw MIDI bytes, with a status byte and no running status.
+    ///
+    /// # Errors
+    ///
+    /// The message is validated first, see [`validate`](MidiMessage::validate).
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MidiError> {
+        self.validate()?;
+
+        let channel = self.channel();
+        Ok(match *self {
+            Self::NoteOff {
+                pitch, velocity, ..
+            } => vec![NOTE_OFF | channel, pitch, velocity],
+            Self::NoteOn {
+                pitch, velocity, ..
+            } => vec![NOTE_ON | channel, pitch, velocity],
+            Self::ControlChange {
+                controller, value, ..
+            } => vec![CONTROL_CHANGE | channel, controller, value],
+            Self::ProgramChange { program, .. } => vec![PROGRAM_CHANGE | channel, program],
+            Self::PitchBend { value, .. } => {
+                let [lsb, msb] = pitch_bend_to_data(value);
+                vec![PITCH_BEND | channel, lsb, msb]
+            }
+            Self::AfterTouch { value, .. } => vec![AFTER_TOUCH | channel, value],
+            Self::PolyAfterTouch { pitch, value, .. } => {
+                vec![POLY_AFTER_TOUCH | channel, pitch, value]
+            }
+        })
+    }
+
+    /// Builds a message from a status byte and its data bytes.
+    fn from_parts(status: u8, data: &[u8]) -> Result<Self, MidiError> {
+        let channel = status & 0x0F;
+        let message = match (status & 0xF0, data) {
+            (NOTE_OFF, &[pitch, velocity]) => Self::NoteOff {
+                channel,
+                pitch,
+                velocity,
+            },
+            (NOTE_ON, &[pitch, velocity]) => Self::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            },
+            (POLY_AFTER_TOUCH, &[pitch, value]) => Self::PolyAfterTouch {
+                channel,
+                pitch,
+                value,
+            },
+            (CONTROL_CHANGE, &[controller, value]) => Self::ControlChange {
+                channel,
+                controller,
+                value,
+            },
+            (PROGRAM_CHANGE, &[program]) => Self::ProgramChange { channel, program },
+            (AFTER_TOUCH, &[value]) => Self::AfterTouch { channel, value },
+            (PITCH_BEND, &[lsb, msb]) => {
+                if lsb > MAX_DATA || msb > MAX_DATA {
+                    return Err(MidiError::DataOutOfRange(lsb.max(msb)));
+                }
+                Self::PitchBend {
+                    channel,
+                    value: ((i16::from(msb) << 7) | i16::from(lsb)) + MIN_PITCH_BEND,
+                }
+            }
+            (NOTE_OFF..=PITCH_BEND, _) => return Err(MidiError::IncompleteMessage),
+            _ => return Err(MidiError::UnsupportedStatus(status)),
+        };
+        message.validate()?;
+        Ok(message)
+    }
+}
+
+/// Splits a validated signed pitch bend value to its 7-bit halves, least significant first.
+#[expect(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation,
+    reason = "The value is validated so the offset value is in the range of 0-16383 and both halves are masked to 7 bits."
+)]
+const fn pitch_bend_to_data(value: i16) -> [u8; 2] {
+    let value = (value - MIN_PITCH_BEND) as u16;
+    [(value & 0x7F) as u8, ((value >> 7) & 0x7F) as u8]
+}
+
+impl TryFrom<&[u8]> for MidiMessage {
+    type Error = MidiError;
+
+    /// Decodes a single complete channel voice message which starts with its status byte.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        match bytes.split_first() {
+            Some((&status, data)) if status & 0x80 != 0 => Self::from_parts(status, data),
+            Some(_) => Err(MidiError::MissingStatus),
+            None => Err(MidiError::IncompleteMessage),
+        }
+    }
+}
+
+/// Decodes a raw MIDI byte stream into [`MidiMessage`]s one byte at a time.
+///
+/// Running status is supported, system real time bytes are skipped without disturbing
+/// the message in progress and system common or exclusive messages are skipped as a whole.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::midi::{MidiMessage, MidiParser};
+///
+/// let mut parser = MidiParser::default();
+/// let messages: Vec<MidiMessage> = [0x90, 60, 100, 62, 100]
+///     .into_iter()
+///     .filter_map(|byte| parser.push(byte))
+///     .collect();
+///
+/// assert_eq!(messages.len(), 2);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MidiParser {
+    status: Option<u8>,
+    data: Vec<u8>,
+}
+
+impl MidiParser {
+    /// Feeds a byte to the parser, returns a message when the byte completes one.
+    pub fn push(&mut self, byte: u8) -> Option<MidiMessage> {
+        match byte {
+            // System real time, may appear anywhere.
+            0xF8..=0xFF => return None,
+            // System common and exclusive, cancels running status.
+            0xF0..=0xF7 => {
+                self.status = None;
+                self.data.clear();
+                return None;
+            }
+            0x80..=0xEF => {
+                self.status = Some(byte);
+                self.data.clear();
+                return None;
+            }
+            _ => {}
+        }
+
+        let status = self.status?;
+        self.data.push(byte);
+
+        let expected = match status & 0xF0 {
+            PROGRAM_CHANGE | AFTER_TOUCH => 1,
+            _ => 2,
+        };
+        if self.data.len() < expected {
+            return None;
+        }
+
+        let message = MidiMessage::from_parts(status, &self.data).ok();
+        self.data.clear();
+        message
+    }
+
+    /// Feeds every byte to the parser and collects the messages they complete.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Vec<MidiMessage> {
+        bytes.iter().filter_map(|byte| self.push(*byte)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+    use super::*;
+
+    #[test]
+    fn round_trip_raw_bytes() {
+        let messages = [
+            MidiMessage::NoteOn {
+                channel: 3,
+                pitch: 60,
+                velocity: 100,
+            },
+            MidiMessage::NoteOff {
+                channel: 0,
+                pitch: 60,
+                velocity: 0,
+            },
+            MidiMessage::ControlChange {
+                channel: 15,
+                controller: 7,
+                value: 127,
+            },
+            MidiMessage::ProgramChange {
+                channel: 1,
+                program: 42,
+            },
+            MidiMessage::PitchBend {
+                channel: 2,
+                value: MIN_PITCH_BEND,
+            },
+            MidiMessage::PitchBend {
+                channel: 2,
+                value: MAX_PITCH_BEND,
+            },
+            MidiMessage::AfterTouch {
+                channel: 4,
+                value: 12,
+            },
+            MidiMessage::PolyAfterTouch {
+                channel: 5,
+                pitch: 64,
+                value: 1,
+            },
+        ];
+
+        for message in messages {
+            let bytes = message.to_bytes().unwrap();
+            assert_eq!(MidiMessage::try_from(bytes.as_slice()).unwrap(), message);
+        }
+        assert_eq!(
+            MidiMessage::PitchBend {
+                channel: 0,
+                value: 0
+            }
+            .to_bytes()
+            .unwrap(),
+            vec![0xE0, 0x00, 0x40]
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert!(matches!(
+            MidiMessage::NoteOn {
+                channel: 16,
+                pitch: 0,
+                velocity: 0
+            }
+            .validate(),
+            Err(MidiError::ChannelOutOfRange(16))
+        ));
+        assert!(matches!(
+            MidiMessage::ControlChange {
+                channel: 0,
+                controller: 128,
+                value: 0
+            }
+            .validate(),
+            Err(MidiError::DataOutOfRange(128))
+        ));
+        assert!(matches!(
+            MidiMessage::PitchBend {
+                channel: 0,
+                value: 8192
+            }
+            .validate(),
+            Err(MidiError::PitchBendOutOfRange(8192))
+        ));
+        assert!(matches!(
+            MidiMessage::try_from([0x90, 60].as_slice()),
+            Err(MidiError::IncompleteMessage)
+        ));
+        assert!(matches!(
+            MidiMessage::try_from([60, 60].as_slice()),
+            Err(MidiError::MissingStatus)
+        ));
+    }
+
+    #[test]
+    fn parse_stream() {
+        let mut parser = MidiParser::default();
+        // Data without status is dropped.
+        let mut messages = parser.push_bytes(&[0x10]);
+        // Note on with running status and a clock in the middle.
+        messages.extend(parser.push_bytes(&[0x91, 60, 0xF8, 100, 61, 0]));
+        // Sysex cancels running status.
+        messages.extend(parser.push_bytes(&[0xF0, 0x7E, 0xF7, 62, 0]));
+        messages.extend(parser.push_bytes(&[0xC2, 5]));
+
+        assert_eq!(
+            messages,
+            vec![
+                MidiMessage::NoteOn {
+                    channel: 1,
+                    pitch: 60,
+                    velocity: 100
+                },
+                MidiMessage::NoteOn {
+                    channel: 1,
+                    pitch: 61,
+                    velocity: 0
+                },
+                MidiMessage::ProgramChange {
+                    channel: 2,
+                    program: 5
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    collections::HashMap,
+    ffi::{c_void, CString},
+    mem,
+    os::raw::c_int,
+    ptr,
+    sync::{LazyLock, Mutex, PoisonError},
+};
+
+use libpd_sys::{t_atom, t_class, t_object, t_outlet, t_symbol};
+
+use crate::{
+    atom::make_t_atom_list_from_atom_list,
+    atoms_from_raw,
+    error::{ObjectError, PdError, StringConversionError},
+    str_from_ptr, Atom,
+};
+
+mod signal;
+
+pub(crate) use signal::register_signal_class;
+pub use signal::PdSignalObject;
+
+/// `CLASS_DEFAULT` in `m_pd.h`, a patchable object with a left inlet.
+const CLASS_DEFAULT: c_int = 0;
+
+/// The classes which are registered, by their names.
+///
+/// Classes are shared by every pd instance, so this is global.
+/// Pointers are stored as `usize` to be able to share them between threads.
+static CLASSES: LazyLock<Mutex<HashMap<String, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// An object which is written in Rust and could be created in patches like any other pd object.
+///
+/// The object is created with the arguments typed in the object box,
+/// the methods are called with the messages sent to its left inlet and
+/// the [`Outlets`] which it could send messages from.
+///
+/// By default a bang, a float or a symbol is handled as a list of zero or one elements
+/// and the rest of the messages are ignored, override the methods which the object needs.
+///
+/// The methods are called from the thread pd runs in, usually the audio thread.
+/// A panic in any of the methods aborts the process since it can not unwind through pd.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{
+///     object::{Outlets, PdObject},
+///     Atom, Pd,
+/// };
+///
+/// /// Sums the floats it receives and outputs the sum.
+/// struct Accumulate {
+///     sum: f64,
+/// }
+///
+/// impl PdObject for Accumulate {
+///     const OUTLETS: usize = 1;
+///
+///     fn new(args: &[Atom]) -> Self {
+///         let sum = match args.first() {
+///             Some(Atom::Float(value)) => *value,
+///             _ => 0.0,
+///         };
+///         Self { sum }
+///     }
+///
+///     fn on_bang(&mut self, outlets: &Outlets) {
+///         outlets.float(0, self.sum).unwrap();
+///     }
+///
+///     fn on_float(&mut self, value: f64, outlets: &Outlets) {
+///         self.sum += value;
+///         self.on_bang(outlets);
+///     }
+///
+///     fn on_message(&mut self, selector: &str, _args: &[Atom], _outlets: &Outlets) {
+///         if selector == "reset" {
+///             self.sum = 0.0;
+///         }
+///     }
+/// }
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.register_class::<Accumulate>("accumulate").unwrap();
+/// // Patches which are opened after this could use [accumulate 10].
+/// pd.open_patch("sequencer.pd").unwrap();
+/// ```
+pub trait PdObject: Send + 'static {
+    /// The amount of outlets of the object, they are created with the object.
+    const OUTLETS: usize = 0;
+
+    /// Creates the object with the arguments in its object box.
+    fn new(args: &[Atom]) -> Self
+    where
+        Self: Sized;
+
+    /// Called with a bang, handled as an empty list by default.
+    fn on_bang(&mut self, outlets: &Outlets) {
+        self.on_list(&[], outlets);
+    }
+
+    /// Called with a float, handled as a list of one float by default.
+    fn on_float(&mut self, value: f64, outlets: &Outlets) {
+        self.on_list(&[Atom::Float(value)], outlets);
+    }
+
+    /// Called with a symbol, handled as a list of one symbol by default.
+    fn on_symbol(&mut self, symbol: &str, outlets: &Outlets) {
+        self.on_list(&[Atom::Symbol(symbol.to_owned())], outlets);
+    }
+
+    /// Called with a list, ignored by default.
+    fn on_list(&mut self, _list: &[Atom], _outlets: &Outlets) {}
+
+    /// Called with any other message, ignored by default.
+    fn on_message(&mut self, _selector: &str, _args: &[Atom], _outlets: &Outlets) {}
+}
+
+/// The outlets of a [`PdObject`], indexed from left to right.
+#[derive(Debug)]
+pub struct Outlets {
+    outlets: Vec<*mut t_outlet>,
+}
+
+impl Outlets {
+    /// Returns the amount of outlets.
+    pub fn len(&self) -> usize {
+        self.outlets.len()
+    }
+
+    /// Checks if the object has no outlets.
+    pub fn is_empty(&self) -> bool {
+        self.outlets.is_empty()
+    }
+
+    /// Sends a bang from an outlet.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutletOutOfRange`](crate::error::ObjectError::OutletOutOfRange)
+    pub fn bang(&self, index: usize) -> Result<(), PdError> {
+        let outlet = self.outlet(index)?;
+        unsafe { libpd_sys::outlet_bang(outlet) };
+        Ok(())
+    }
+
+    /// Sends a float from an outlet.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutletOutOfRange`](crate::error::ObjectError::OutletOutOfRange)
+    pub fn float(&self, index: usize, value: f64) -> Result<(), PdError> {
+        let outlet = self.outlet(index)?;
+        unsafe { libpd_sys::outlet_float(outlet, value) };
+        Ok(())
+    }
+
+    /// Sends a symbol from an outlet.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutletOutOfRange`](crate::error::ObjectError::OutletOutOfRange)
+    /// - [`StringConversion`](crate::error::PdError::StringConversion)
+    pub fn symbol(&self, index: usize, symbol: &str) -> Result<(), PdError> {
+        let outlet = self.outlet(index)?;
+        let symbol = symbol_from_str(symbol)?;
+        unsafe { libpd_sys::outlet_symbol(outlet, symbol) };
+        Ok(())
+    }
+
+    /// Sends a list from an outlet.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutletOutOfRange`](crate::error::ObjectError::OutletOutOfRange)
+    /// - [`StringConversion`](crate::error::PdError::StringConversion)
+    pub fn list(&self, index: usize, list: &[Atom]) -> Result<(), PdError> {
+        self.message(index, "list", list)
+    }
+
+    /// Sends a message with a selector from an outlet.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutletOutOfRange`](crate::error::ObjectError::OutletOutOfRange)
+    /// - [`StringConversion`](crate::error::PdError::StringConversion)
+    pub fn message(&self, index: usize, selector: &str, args: &[Atom]) -> Result<(), PdError> {
+        let outlet = self.outlet(index)?;
+        let selector = symbol_from_str(selector)?;
+        let mut atoms = make_t_atom_list_from_atom_list(args)?;
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_possible_wrap,
+            reason = "Messages do not have more elements than i32::MAX."
+        )]
+        let length = atoms.len() as c_int;
+        unsafe {
+            libpd_sys::outlet_anything(outlet, selector, length, atoms.as_mut_ptr());
+        }
+        Ok(())
+    }
+
+    fn outlet(&self, index: usize) -> Result<*mut t_outlet, ObjectError> {
+        self.outlets
+            .get(index)
+            .copied()
+            .ok_or(ObjectError::OutletOutOfRange {
+                index,
+                outlets: self.outlets.len(),
+            })
+    }
+}
+
+/// The memory pd allocates for an object, the pd object header needs to be the first field.
+#[repr(C)]
+struct ObjectData<T> {
+    object: t_object,
+    outlets: Outlets,
+    state: T,
+}
+
+/// The signature of the method which creates objects of a class from the arguments in their object boxes.
+type NewMethod = unsafe extern "C" fn(*mut t_symbol, c_int, *mut t_atom) -> *mut c_void;
+
+/// Registers a class with the current instance, the class is shared with every other instance.
+pub(crate) fn register_class<T: PdObject>(name: &str) -> Result<(), ObjectError> {
+    new_class(
+        name,
+        new_object::<T>,
+        method(free_object::<T> as unsafe extern "C" fn(_)),
+        mem::size_of::<ObjectData<T>>(),
+        |class| unsafe {
+            libpd_sys::class_addbang(class, Some(method(bang::<T> as unsafe extern "C" fn(_))));
+            libpd_sys::class_doaddfloat(
+                class,
+                Some(method(float::<T> as unsafe extern "C" fn(_, _))),
+            );
+            libpd_sys::class_addsymbol(
+                class,
+                Some(method(symbol::<T> as unsafe extern "C" fn(_, _))),
+            );
+            libpd_sys::class_addlist(
+                class,
+                Some(method(list::<T> as unsafe extern "C" fn(_, _, _, _))),
+            );
+            libpd_sys::class_addanything(
+                class,
+                Some(method(anything::<T> as unsafe extern "C" fn(_, _, _, _))),
+            );
+        },
+    )
+}
+
+/// Creates a class which takes any arguments, adds its methods and stores it by its name.
+fn new_class<F: FnOnce(*mut t_class)>(
+    name: &str,
+    new: NewMethod,
+    free: unsafe extern "C" fn(),
+    size: usize,
+    add_methods: F,
+) -> Result<(), ObjectError> {
+    let mut classes = CLASSES.lock().unwrap_or_else(PoisonError::into_inner);
+    if classes.contains_key(name) {
+        return Err(ObjectError::AlreadyRegistered(name.to_owned()));
+    }
+    let c_name = CString::new(name).map_err(StringConversionError::from)?;
+
+    // libpd is built with 64-bit floats, `class_new` refuses to create classes for it.
+    let class = unsafe {
+        libpd_sys::class_new64(
+            libpd_sys::gensym(c_name.as_ptr()),
+            Some(mem::transmute::<
+                NewMethod,
+                unsafe extern "C" fn() -> *mut c_void,
+            >(new)),
+            Some(free),
+            size,
+            CLASS_DEFAULT,
+            libpd_sys::t_atomtype_A_GIMME,
+            libpd_sys::t_atomtype_A_NULL,
+        )
+    };
+    if class.is_null() {
+        return Err(ObjectError::FailedToRegister(name.to_owned()));
+    }
+    add_methods(class);
+
+    classes.insert(name.to_owned(), class as usize);
+    Ok(())
+}
+
+/// Finds the class of an object which is being created by the name it is typed with.
+fn class_named(name: *mut t_symbol) -> Option<*mut t_class> {
+    let name = unsafe { str_from_ptr((*name).s_name) };
+    CLASSES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .map(|class| *class as *mut t_class)
+}
+
+/// Erases the signature of a method the way pd expects, pd calls it back with the signature it is registered with.
+fn method<F: Copy>(function: F) -> unsafe extern "C" fn() {
+    unsafe { mem::transmute_copy::<F, unsafe extern "C" fn()>(&function) }
+}
+
+/// Converts the arguments of a method to atoms.
+fn atoms(argc: c_int, argv: *mut t_atom) -> Vec<Atom> {
+    if argc <= 0 || argv.is_null() {
+        return Vec::new();
+    }
+    atoms_from_raw(argc, argv)
+}
+
+fn symbol_from_str(symbol: &str) -> Result<*mut t_symbol, StringConversionError> {
+    let symbol = CString::new(symbol)?;
+    Ok(unsafe { libpd_sys::gensym(symbol.as_ptr()) })
+}
+
+unsafe extern "C" fn new_object<T: PdObject>(
+    name: *mut t_symbol,
+    argc: c_int,
+    argv: *mut t_atom,
+) -> *mut c_void {
+    let Some(class) = class_named(name) else {
+        return ptr::null_mut();
+    };
+    let data = unsafe { libpd_sys::pd_new(class) }.cast::<ObjectData<T>>();
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+
+    let owner = data.cast::<t_object>();
+    let outlets = (0..T::OUTLETS)
+        .map(|_| unsafe { libpd_sys::outlet_new(owner, ptr::null_mut()) })
+        .collect();
+    // pd allocates the memory zeroed, the fields are written without reading or dropping what is there.
+    unsafe {
+        ptr::addr_of_mut!((*data).outlets).write(Outlets { outlets });
+        ptr::addr_of_mut!((*data).state).write(T::new(&atoms(argc, argv)));
+    }
+    data.cast()
+}
+
+unsafe extern "C" fn free_object<T: PdObject>(data: *mut ObjectData<T>) {
+    // pd frees the outlets and the memory of the object itself.
+    unsafe {
+        ptr::drop_in_place(ptr::addr_of_mut!((*data).state));
+        ptr::drop_in_place(ptr::addr_of_mut!((*data).outlets));
+    }
+}
+
+unsafe extern "C" fn bang<T: PdObject>(data: *mut ObjectData<T>) {
+    let ObjectData { outlets, state, .. } = unsafe { &mut *data };
+    state.on_bang(outlets);
+}
+
+unsafe extern "C" fn float<T: PdObject>(data: *mut ObjectData<T>, value: f64) {
+    let ObjectData { outlets, state, .. } = unsafe { &mut *data };
+    state.on_float(value, outlets);
+}
+
+unsafe extern "C" fn symbol<T: PdObject>(data: *mut ObjectData<T>, symbol: *mut t_symbol) {
+    let ObjectData { outlets, state, .. } = unsafe { &mut *data };
+    state.on_symbol(unsafe { str_from_ptr((*symbol).s_name) }, outlets);
+}
+
+unsafe extern "C" fn list<T: PdObject>(
+    data: *mut ObjectData<T>,
+    _selector: *mut t_symbol,
+    argc: c_int,
+    argv: *mut t_atom,
+) {
+    let ObjectData { outlets, state, .. } = unsafe { &mut *data };
+    state.on_list(&atoms(argc, argv), outlets);
+}
+
+unsafe extern "C" fn anything<T: PdObject>(
+    data: *mut ObjectData<T>,
+    selector: *mut t_symbol,
+    argc: c_int,
+    argv: *mut t_atom,
+) {
+    let ObjectData { outlets, state, .. } = unsafe { &mut *data };
+    state.on_message(
+        unsafe { str_from_ptr((*selector).s_name) },
+        &atoms(argc, argv),
+        outlets,
+    );
+}
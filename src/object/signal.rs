@@ -0,0 +1,283 @@
+use std::{ffi::c_void, mem, os::raw::c_int, ptr, slice};
+
+use libpd_sys::{t_atom, t_float, t_int, t_object, t_sample, t_signal, t_symbol};
+
+use super::{atoms, class_named, method, new_class, symbol_from_str};
+use crate::{error::ObjectError, str_from_ptr, Atom};
+
+/// A signal object which is written in Rust and could be created in patches like any other `~` object.
+///
+/// The object is created with the arguments typed in the object box and processes
+/// one block of every signal inlet to every signal outlet each time pd ticks.
+///
+/// The leftmost inlet is always a signal inlet, floats sent to a signal inlet
+/// set the value of the inlet while no signal is connected to it.
+///
+/// pd computes signals in 64 bits, the samples are converted to `f32` before and after processing.
+/// Inputs are copied before processing, so outputs never alias them.
+///
+/// The methods are called from the thread pd runs in, usually the audio thread.
+/// A panic in any of the methods aborts the process since it can not unwind through pd.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{object::PdSignalObject, Atom, Pd};
+///
+/// /// Multiplies its input with the gain it is created with.
+/// struct Gain {
+///     gain: f32,
+/// }
+///
+/// impl PdSignalObject for Gain {
+///     fn new(args: &[Atom]) -> Self {
+///         let gain = match args.first() {
+///             Some(Atom::Float(value)) => *value as f32,
+///             _ => 1.0,
+///         };
+///         Self { gain }
+///     }
+///
+///     fn process(&mut self, inputs: &[&[f32]], outputs: &mut [&mut [f32]]) {
+///         for (output, input) in outputs[0].iter_mut().zip(inputs[0]) {
+///             *output = input * self.gain;
+///         }
+///     }
+/// }
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.register_signal_class::<Gain>("gain~").unwrap();
+/// // Patches which are opened after this could use [gain~ 0.5].
+/// pd.open_patch("synth.pd").unwrap();
+/// ```
+pub trait PdSignalObject: Send + 'static {
+    /// The amount of signal inlets including the leftmost one, less than `1` is treated as `1`.
+    const INLETS: usize = 1;
+    /// The amount of signal outlets.
+    const OUTLETS: usize = 1;
+
+    /// Creates the object with the arguments in its object box.
+    fn new(args: &[Atom]) -> Self
+    where
+        Self: Sized;
+
+    /// Called with the sample rate and the block size each time the DSP graph which contains the object is built.
+    ///
+    /// This happens when DSP is turned on and when the patches change while it is on,
+    /// always before the first call to [`process`](PdSignalObject::process).
+    fn prepare(&mut self, _sample_rate: f64, _block_size: usize) {}
+
+    /// Processes one block, there is a slice of the block size for every inlet and every outlet.
+    fn process(&mut self, inputs: &[&[f32]], outputs: &mut [&mut [f32]]);
+
+    /// Called with any message other than a float which is sent to the leftmost inlet, ignored by default.
+    fn on_message(&mut self, _selector: &str, _args: &[Atom]) {}
+}
+
+/// The memory pd allocates for a signal object, the pd object header needs to be the first field.
+#[repr(C)]
+struct SignalData<T> {
+    object: t_object,
+    /// The value of the leftmost inlet while no signal is connected to it.
+    scalar: t_float,
+    block: Block,
+    state: T,
+}
+
+/// The signals of the object in the DSP graph and the buffers they are converted into.
+struct Block {
+    size: usize,
+    /// The signals of the inlets followed by the signals of the outlets.
+    signals: Vec<*mut t_sample>,
+    samples: Vec<f32>,
+    inputs: Vec<&'static [f32]>,
+    outputs: Vec<&'static mut [f32]>,
+}
+
+/// Registers a signal class with the current instance, the class is shared with every other instance.
+pub(crate) fn register_signal_class<T: PdSignalObject>(name: &str) -> Result<(), ObjectError> {
+    let dsp = symbol_from_str("dsp")?;
+    new_class(
+        name,
+        new_signal_object::<T>,
+        method(free_signal_object::<T> as unsafe extern "C" fn(_)),
+        mem::size_of::<SignalData<T>>(),
+        |class| unsafe {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_possible_wrap,
+                reason = "The offset of a field is far below i32::MAX."
+            )]
+            let scalar_offset = mem::offset_of!(SignalData<T>, scalar) as c_int;
+            libpd_sys::class_domainsignalin(class, scalar_offset);
+            libpd_sys::class_addmethod(
+                class,
+                Some(method(dsp_method::<T> as unsafe extern "C" fn(_, _))),
+                dsp,
+                libpd_sys::t_atomtype_A_CANT,
+                libpd_sys::t_atomtype_A_NULL,
+            );
+            libpd_sys::class_addanything(
+                class,
+                Some(method(
+                    signal_anything::<T> as unsafe extern "C" fn(_, _, _, _),
+                )),
+            );
+        },
+    )
+}
+
+const fn inlet_count<T: PdSignalObject>() -> usize {
+    if T::INLETS == 0 {
+        1
+    } else {
+        T::INLETS
+    }
+}
+
+/// Reuses the allocation of an empty vector for references with another lifetime.
+fn recycle<'a, 'b, R: ?Sized>(mut references: Vec<&'a mut R>) -> Vec<&'b mut R> {
+    references.clear();
+    references.into_iter().filter_map(|_| None).collect()
+}
+
+/// Reuses the allocation of an empty vector for shared references with another lifetime.
+fn recycle_shared<'a, 'b, R: ?Sized>(mut references: Vec<&'a R>) -> Vec<&'b R> {
+    references.clear();
+    references.into_iter().filter_map(|_| None).collect()
+}
+
+unsafe extern "C" fn new_signal_object<T: PdSignalObject>(
+    name: *mut t_symbol,
+    argc: c_int,
+    argv: *mut t_atom,
+) -> *mut c_void {
+    let Some(class) = class_named(name) else {
+        return ptr::null_mut();
+    };
+    let data = unsafe { libpd_sys::pd_new(class) }.cast::<SignalData<T>>();
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+
+    let owner = data.cast::<t_object>();
+    let Ok(signal) = symbol_from_str("signal") else {
+        return ptr::null_mut();
+    };
+    unsafe {
+        // The leftmost inlet is created by pd.
+        for _ in 1..inlet_count::<T>() {
+            libpd_sys::signalinlet_new(owner, 0.0);
+        }
+        for _ in 0..T::OUTLETS {
+            libpd_sys::outlet_new(owner, signal);
+        }
+    }
+    // pd allocates the memory zeroed, the fields are written without reading or dropping what is there.
+    unsafe {
+        ptr::addr_of_mut!((*data).block).write(Block {
+            size: 0,
+            signals: Vec::new(),
+            samples: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        });
+        ptr::addr_of_mut!((*data).state).write(T::new(&atoms(argc, argv)));
+    }
+    data.cast()
+}
+
+unsafe extern "C" fn free_signal_object<T: PdSignalObject>(data: *mut SignalData<T>) {
+    // pd frees the inlets, the outlets and the memory of the object itself.
+    unsafe {
+        ptr::drop_in_place(ptr::addr_of_mut!((*data).state));
+        ptr::drop_in_place(ptr::addr_of_mut!((*data).block));
+    }
+}
+
+/// Called by pd when the DSP graph is built, adds the object to it.
+unsafe extern "C" fn dsp_method<T: PdSignalObject>(
+    data: *mut SignalData<T>,
+    signals: *mut *mut t_signal,
+) {
+    let channels = inlet_count::<T>() + T::OUTLETS;
+    let signals = unsafe { slice::from_raw_parts(signals, channels) };
+    let Some(first) = signals.first() else {
+        return;
+    };
+    let (size, sample_rate) = unsafe { ((**first).__bindgen_anon_1.s_length, (**first).s_sr) };
+    #[expect(
+        clippy::cast_sign_loss,
+        reason = "pd never builds a DSP graph with a negative block size."
+    )]
+    let size = size as usize;
+
+    let SignalData { block, state, .. } = unsafe { &mut *data };
+    block.size = size;
+    block.signals.clear();
+    block
+        .signals
+        .extend(signals.iter().map(|signal| unsafe { (**signal).s_vec }));
+    block.samples.clear();
+    block.samples.resize(size * channels, 0.0);
+    block.inputs.reserve(inlet_count::<T>());
+    block.outputs.reserve(T::OUTLETS);
+    state.prepare(sample_rate, size);
+
+    unsafe {
+        libpd_sys::dsp_add(Some(perform::<T>), 1, data as t_int);
+    }
+}
+
+/// Called by pd to process a block, the argument after the routine is the object.
+unsafe extern "C" fn perform<T: PdSignalObject>(arguments: *mut t_int) -> *mut t_int {
+    let data = unsafe { *arguments.add(1) } as *mut SignalData<T>;
+    let SignalData { block, state, .. } = unsafe { &mut *data };
+    let (input_signals, output_signals) = block.signals.split_at(inlet_count::<T>());
+    let (input_samples, output_samples) =
+        block.samples.split_at_mut(inlet_count::<T>() * block.size);
+
+    // pd may process in place, so every input is copied before any output is written.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Samples are converted to the f32 the objects process."
+    )]
+    for (samples, signal) in input_samples
+        .chunks_exact_mut(block.size)
+        .zip(input_signals)
+    {
+        let signal = unsafe { slice::from_raw_parts(*signal, block.size) };
+        for (sample, value) in samples.iter_mut().zip(signal) {
+            *sample = *value as f32;
+        }
+    }
+
+    let mut inputs = recycle_shared(mem::take(&mut block.inputs));
+    let mut outputs = recycle(mem::take(&mut block.outputs));
+    inputs.extend(input_samples.chunks_exact(block.size));
+    outputs.extend(output_samples.chunks_exact_mut(block.size));
+    state.process(&inputs, &mut outputs);
+
+    for (samples, signal) in outputs.iter().zip(output_signals) {
+        let signal = unsafe { slice::from_raw_parts_mut(*signal, block.size) };
+        for (value, sample) in signal.iter_mut().zip(samples.iter()) {
+            *value = f64::from(*sample);
+        }
+    }
+    block.inputs = recycle_shared(inputs);
+    block.outputs = recycle(outputs);
+
+    unsafe { arguments.add(2) }
+}
+
+unsafe extern "C" fn signal_anything<T: PdSignalObject>(
+    data: *mut SignalData<T>,
+    selector: *mut t_symbol,
+    argc: c_int,
+    argv: *mut t_atom,
+) {
+    let SignalData { state, .. } = unsafe { &mut *data };
+    state.on_message(
+        unsafe { str_from_ptr((*selector).s_name) },
+        &atoms(argc, argv),
+    );
+}
@@ -0,0 +1,770 @@
+use std::{
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    error::{OscError, PdError},
+    fudi::{is_timeout, MAX_DATAGRAM, POLL_INTERVAL},
+    schedule::{ScheduleSender, ScheduledMessage, Timestamp},
+    types::{AddressedMessage, PdMessage},
+    Atom, Pd,
+};
+
+/// The first element of every bundle.
+const BUNDLE_TAG: &[u8] = b"#bundle\0";
+
+/// The seconds between the epoch of OSC time tags, 1900, and the unix epoch.
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// An argument of an OSC message.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum OscArg {
+    /// A 32-bit integer, type tag `i`.
+    Int(i32),
+    /// A 32-bit float, type tag `f`.
+    Float(f32),
+    /// A string, type tag `s`.
+    String(String),
+    /// Raw bytes, type tag `b`.
+    Blob(Vec<u8>),
+}
+
+impl OscArg {
+    const fn tag(&self) -> u8 {
+        match self {
+            Self::Int(_) => b'i',
+            Self::Float(_) => b'f',
+            Self::String(_) => b's',
+            Self::Blob(_) => b'b',
+        }
+    }
+
+    /// Appends the argument to a list of atoms, every byte of a blob becomes a float.
+    fn push_atoms(&self, atoms: &mut Vec<Atom>) {
+        match self {
+            Self::Int(value) => atoms.push(Atom::Float(f64::from(*value))),
+            Self::Float(value) => atoms.push(Atom::Float(f64::from(*value))),
+            Self::String(value) => atoms.push(Atom::Symbol(value.clone())),
+            Self::Blob(bytes) => {
+                atoms.extend(bytes.iter().map(|byte| Atom::Float(f64::from(*byte))))
+            }
+        }
+    }
+}
+
+impl From<&Atom> for OscArg {
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Floats in OSC messages are 32-bit."
+    )]
+    fn from(atom: &Atom) -> Self {
+        match atom {
+            Atom::Float(value) => Self::Float(*value as f32),
+            Atom::Symbol(symbol) => Self::String(symbol.clone()),
+        }
+    }
+}
+
+/// The time an OSC bundle takes effect, in the NTP format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OscTimeTag {
+    /// Seconds since 1900.
+    pub seconds: u32,
+    /// Fractions of a second in units of `1 / 2^32` seconds.
+    pub fraction: u32,
+}
+
+impl OscTimeTag {
+    /// The special time tag which means the bundle takes effect right away.
+    pub const IMMEDIATELY: Self = Self {
+        seconds: 0,
+        fraction: 1,
+    };
+
+    /// Checks if the time tag is [`IMMEDIATELY`](OscTimeTag::IMMEDIATELY).
+    pub const fn is_immediate(self) -> bool {
+        self.seconds == 0 && self.fraction == 1
+    }
+
+    /// Converts the time tag to a point in time, `None` if it is immediate or not representable.
+    pub fn to_system_time(self) -> Option<SystemTime> {
+        if self.is_immediate() {
+            return None;
+        }
+        let nanos = (u64::from(self.fraction) * 1_000_000_000) >> 32;
+        SystemTime::UNIX_EPOCH
+            .checked_sub(Duration::from_secs(NTP_UNIX_OFFSET))?
+            .checked_add(Duration::from_secs(u64::from(self.seconds)))?
+            .checked_add(Duration::from_nanos(nanos))
+    }
+
+    /// Converts a point in time to a time tag, times before 1970 are clamped to 1970.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let since_epoch = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "Seconds wrap around in 2036 like they do in the NTP format."
+        )]
+        let seconds = (since_epoch.as_secs() + NTP_UNIX_OFFSET) as u32;
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "A fraction of a second is less than 2^32."
+        )]
+        let fraction = (u64::from(since_epoch.subsec_nanos()) << 32)
+            .checked_div(1_000_000_000)
+            .unwrap_or_default() as u32;
+        Self { seconds, fraction }
+    }
+}
+
+/// An OSC message, an address and its arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    /// The address pattern of the message, e.g. `/synth/cutoff`.
+    pub address: String,
+    /// The arguments of the message.
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    /// Creates a message.
+    pub fn new<T: Into<String>>(address: T, args: Vec<OscArg>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+}
+
+/// An OSC bundle, packets which take effect at the same time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscBundle {
+    /// The time the packets take effect.
+    pub timetag: OscTimeTag,
+    /// The messages and the bundles in the bundle.
+    pub content: Vec<OscPacket>,
+}
+
+/// A unit of OSC data, which is sent in a single datagram.
+///
+/// # Example
+/// ```
+/// use libpd_rs::osc::{OscArg, OscMessage, OscPacket};
+///
+/// let packet = OscPacket::Message(OscMessage::new("/synth/cutoff", vec![OscArg::Float(0.5)]));
+/// assert_eq!(OscPacket::decode(&packet.encode()).unwrap(), packet);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum OscPacket {
+    /// A single message.
+    Message(OscMessage),
+    /// A bundle of packets.
+    Bundle(OscBundle),
+}
+
+impl OscPacket {
+    /// Encodes the packet in the OSC 1.0 binary format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        encode_packet(&mut out, self);
+        out
+    }
+
+    /// Decodes a packet in the OSC 1.0 binary format.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OscError`]
+    ///   - [`UnexpectedEnd`](crate::error::OscError::UnexpectedEnd)
+    ///   - [`InvalidPacket`](crate::error::OscError::InvalidPacket)
+    ///   - [`InvalidSize`](crate::error::OscError::InvalidSize)
+    ///   - [`UnsupportedType`](crate::error::OscError::UnsupportedType)
+    ///   - [`InvalidUtf8`](crate::error::OscError::InvalidUtf8)
+    pub fn decode(bytes: &[u8]) -> Result<Self, OscError> {
+        let mut reader = Reader { bytes };
+        if bytes.starts_with(BUNDLE_TAG) {
+            reader.take(BUNDLE_TAG.len())?;
+            let timetag = OscTimeTag {
+                seconds: reader.u32()?,
+                fraction: reader.u32()?,
+            };
+            let mut content = Vec::new();
+            while !reader.bytes.is_empty() {
+                let size = reader.size()?;
+                content.push(Self::decode(reader.take(size)?)?);
+            }
+            return Ok(Self::Bundle(OscBundle { timetag, content }));
+        }
+        if !bytes.starts_with(b"/") {
+            return Err(OscError::InvalidPacket);
+        }
+
+        let address = reader.string()?.to_owned();
+        // Old implementations may leave the type tags out when there are no arguments.
+        let tags = if reader.bytes.is_empty() {
+            ","
+        } else {
+            reader.string()?
+        };
+        let tags = tags.strip_prefix(',').ok_or(OscError::InvalidPacket)?;
+        let args = tags
+            .chars()
+            .map(|tag| match tag {
+                'i' => reader.i32().map(OscArg::Int),
+                'f' => reader.u32().map(|bits| OscArg::Float(f32::from_bits(bits))),
+                's' => reader
+                    .string()
+                    .map(|value| OscArg::String(value.to_owned())),
+                'b' => {
+                    let size = reader.size()?;
+                    reader
+                        .padded(size)
+                        .map(|bytes| OscArg::Blob(bytes.to_vec()))
+                }
+                tag => Err(OscError::UnsupportedType(tag)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::Message(OscMessage { address, args }))
+    }
+}
+
+fn encode_packet(out: &mut Vec<u8>, packet: &OscPacket) {
+    match packet {
+        OscPacket::Message(message) => {
+            write_padded(out, message.address.as_bytes(), true);
+            let tags: Vec<u8> = core::iter::once(b',')
+                .chain(message.args.iter().map(OscArg::tag))
+                .collect();
+            write_padded(out, &tags, true);
+            for arg in &message.args {
+                match arg {
+                    OscArg::Int(value) => out.extend_from_slice(&value.to_be_bytes()),
+                    OscArg::Float(value) => out.extend_from_slice(&value.to_be_bytes()),
+                    OscArg::String(value) => write_padded(out, value.as_bytes(), true),
+                    OscArg::Blob(bytes) => {
+                        write_size(out, bytes.len());
+                        write_padded(out, bytes, false);
+                    }
+                }
+            }
+        }
+        OscPacket::Bundle(bundle) => {
+            out.extend_from_slice(BUNDLE_TAG);
+            out.extend_from_slice(&bundle.timetag.seconds.to_be_bytes());
+            out.extend_from_slice(&bundle.timetag.fraction.to_be_bytes());
+            for packet in &bundle.content {
+                let element = packet.encode();
+                write_size(out, element.len());
+                out.extend_from_slice(&element);
+            }
+        }
+    }
+}
+
+/// Writes the bytes padded with zeros to a multiple of 4, strings are terminated with at least one zero.
+fn write_padded(out: &mut Vec<u8>, bytes: &[u8], terminated: bool) {
+    out.extend_from_slice(bytes);
+    let len = bytes.len() + usize::from(terminated);
+    out.resize(out.len() + len.next_multiple_of(4) - bytes.len(), 0);
+}
+
+fn write_size(out: &mut Vec<u8>, size: usize) {
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        reason = "Packets fit in a datagram which is far smaller than i32::MAX."
+    )]
+    let size = size as i32;
+    out.extend_from_slice(&size.to_be_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OscError> {
+        let (taken, rest) = self
+            .bytes
+            .split_at_checked(len)
+            .ok_or(OscError::UnexpectedEnd)?;
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Takes the bytes and skips the padding after them.
+    fn padded(&mut self, len: usize) -> Result<&'a [u8], OscError> {
+        let taken = self.take(len)?;
+        self.take(len.next_multiple_of(4) - len)?;
+        Ok(taken)
+    }
+
+    fn string(&mut self) -> Result<&'a str, OscError> {
+        let len = self
+            .bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or(OscError::UnexpectedEnd)?;
+        let terminated = self.padded(len + 1)?;
+        let text = terminated.get(..len).ok_or(OscError::UnexpectedEnd)?;
+        Ok(core::str::from_utf8(text)?)
+    }
+
+    fn word(&mut self) -> Result<[u8; 4], OscError> {
+        self.take(4)?
+            .try_into()
+            .map_err(|_| OscError::UnexpectedEnd)
+    }
+
+    fn i32(&mut self) -> Result<i32, OscError> {
+        self.word().map(i32::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, OscError> {
+        self.word().map(u32::from_be_bytes)
+    }
+
+    /// Reads the size of a blob or a bundle element.
+    fn size(&mut self) -> Result<usize, OscError> {
+        let size = self.i32()?;
+        usize::try_from(size).map_err(|_| OscError::InvalidSize(size))
+    }
+}
+
+/// A rule which maps OSC addresses to a pd receiver, added with [`OscBridge::route`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OscRoute {
+    address: String,
+    receiver: String,
+    /// Set for the routes which end with `/*`, the rest of the address becomes the selector of the message.
+    prefix: bool,
+}
+
+/// Forwards OSC messages between a local UDP socket and a [`Pd`] instance.
+///
+/// Messages which arrive are sent to the receiver their address maps to:
+/// - No arguments are sent as a bang, a single number as a float and a single string as a symbol.
+/// - Other arguments are sent as a list, every byte of a blob becomes a float.
+///
+/// Addresses map to receivers with the rules added by [`route`](OscBridge::route).
+/// Addresses without a rule map to the receiver named after the address with its slashes turned into underscores,
+/// `/synth/cutoff` is sent to `synth_cutoff`, unless [`default_routes`](OscBridge::default_routes) is turned off.
+/// Messages which don't map to a receiver are dropped.
+///
+/// Messages sent to the sources which are subscribed with [`subscribe_to`](Pd::subscribe_to) are sent to the peer,
+/// mapped back to addresses with the same rules.
+/// A message with a selector other than `bang`, `float`, `symbol` and `list` gets the selector as its first argument,
+/// or as the last part of the address for the routes which end with `/*`.
+///
+/// The messages in bundles with a time tag are sent at that time when a [`ScheduleSender`] is given with
+/// [`scheduler`](OscBridge::scheduler), otherwise they are sent right away.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{osc::OscBridge, Pd};
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("synth.pd").unwrap();
+///
+/// let bridge = OscBridge::udp(&mut pd, "127.0.0.1:9000", "127.0.0.1:9001")
+///     .unwrap()
+///     // `/synth/cutoff 0.5` is sent to `synth` as `cutoff 0.5`.
+///     .route("/synth/*", "synth")
+///     .route("/meter", "level");
+/// bridge.subscribe(&mut pd).unwrap();
+///
+/// loop {
+///     // The audio thread calls receive_messages_from_pd on its context.
+///     bridge.forward(&pd).unwrap();
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct OscBridge {
+    socket: UdpSocket,
+    local_addr: SocketAddr,
+    peer: SocketAddr,
+    routes: Vec<OscRoute>,
+    default_routes: bool,
+    scheduler: Option<ScheduleSender>,
+    incoming: mpsc::Receiver<Result<OscPacket, OscError>>,
+    outgoing: mpsc::Receiver<PdMessage>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OscBridge {
+    /// Listens for OSC packets on the address and sends the messages from pd to the peer.
+    ///
+    /// Port `0` picks a free port, see [`local_addr`](OscBridge::local_addr).
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OscError`]
+    ///   - [`Io`](crate::error::OscError::Io)
+    pub fn udp<A: ToSocketAddrs, P: ToSocketAddrs>(
+        pd: &mut Pd,
+        address: A,
+        peer: P,
+    ) -> Result<Self, OscError> {
+        let socket = UdpSocket::bind(address)?;
+        let local_addr = socket.local_addr()?;
+        let peer = peer.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "No address to send to.")
+        })?;
+        let reader = socket.try_clone()?;
+        reader.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let (sender, incoming) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || receive_packets(&reader, &sender, &stop))
+        };
+
+        Ok(Self {
+            socket,
+            local_addr,
+            peer,
+            routes: Vec::new(),
+            default_routes: true,
+            scheduler: None,
+            incoming,
+            outgoing: pd.message_receiver(),
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Maps an address to a receiver in both directions.
+    ///
+    /// An address which ends with `/*` maps every address under it,
+    /// the rest of the address becomes the selector of the message, e.g. with `/synth/*` mapped to `synth`
+    /// `/synth/cutoff 0.5` is sent to `synth` as `cutoff 0.5`.
+    ///
+    /// Exact addresses are matched first, then the longest address which ends with `/*`.
+    #[must_use]
+    pub fn route<A: Into<String>, R: Into<String>>(mut self, address: A, receiver: R) -> Self {
+        let address = address.into();
+        let (address, prefix) = match address.strip_suffix("/*") {
+            Some(prefix) => (prefix.to_owned(), true),
+            None => (address, false),
+        };
+        self.routes.push(OscRoute {
+            address,
+            receiver: receiver.into(),
+            prefix,
+        });
+        self
+    }
+
+    /// Sets if the addresses without a rule map to the receivers named after them, `true` by default.
+    #[must_use]
+    pub const fn default_routes(mut self, enabled: bool) -> Self {
+        self.default_routes = enabled;
+        self
+    }
+
+    /// Schedules the messages in bundles with a time tag, see [`Pd::scheduler`].
+    #[must_use]
+    pub fn scheduler(mut self, scheduler: ScheduleSender) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Subscribes to the receivers of the rules, so the messages sent to them in pd are sent out.
+    ///
+    /// The messages which arrive to these receivers from the bridge are sent back out too,
+    /// subscribe with [`subscribe_to`](Pd::subscribe_to) to pick the sources one by one instead.
+    ///
+    /// # Errors
+    ///
+    /// See [`subscribe_to`](Pd::subscribe_to).
+    pub fn subscribe(&self, pd: &mut Pd) -> Result<(), PdError> {
+        for route in &self.routes {
+            pd.subscribe_to(&route.receiver)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the address the bridge listens on.
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Sends the messages from pd out and the messages which arrived since the last call to pd.
+    ///
+    /// Messages from pd are collected while its queues are drained with
+    /// [`receive_messages_from_pd`](crate::PdAudioContext::receive_messages_from_pd).
+    ///
+    /// Returns the amount of messages which are sent or scheduled to pd.
+    /// Packets after the one which fails are kept for the next call.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OscError`]
+    ///   - See [`OscPacket::decode`] for the errors of the packets which arrived.
+    ///   - [`Pd`](crate::error::OscError::Pd)
+    ///   - [`Io`](crate::error::OscError::Io)
+    pub fn forward(&self, pd: &Pd) -> Result<usize, OscError> {
+        for message in self.outgoing.try_iter() {
+            if let Some(message) = self.osc_message(&message) {
+                self.socket
+                    .send_to(&OscPacket::Message(message).encode(), self.peer)?;
+            }
+        }
+
+        let _guard = pd.set_as_active_instance();
+        let mut forwarded = 0;
+        for packet in self.incoming.try_iter() {
+            forwarded += self.dispatch(&packet?, None)?;
+        }
+        Ok(forwarded)
+    }
+
+    /// Sends or schedules the messages in the packet, returns the amount of them.
+    fn dispatch(&self, packet: &OscPacket, time: Option<SystemTime>) -> Result<usize, PdError> {
+        match packet {
+            OscPacket::Message(message) => {
+                let Some(message) = self.pd_message(message) else {
+                    return Ok(0);
+                };
+                match (time, &self.scheduler) {
+                    (Some(time), Some(scheduler)) => {
+                        scheduler.send(Timestamp::Time(time), message)?;
+                    }
+                    _ => message.send()?,
+                }
+                Ok(1)
+            }
+            OscPacket::Bundle(bundle) => {
+                // Nested bundles which are immediate take effect with the bundle they are in.
+                let time = bundle.timetag.to_system_time().or(time);
+                bundle
+                    .content
+                    .iter()
+                    .try_fold(0, |sent, packet| Ok(sent + self.dispatch(packet, time)?))
+            }
+        }
+    }
+
+    /// Maps a message which arrived to the message which is sent to pd.
+    fn pd_message(&self, message: &OscMessage) -> Option<ScheduledMessage> {
+        let (receiver, selector) = self.receiver_for(&message.address)?;
+        let mut list = Vec::new();
+        for arg in &message.args {
+            arg.push_atoms(&mut list);
+        }
+        Some(match (selector, list.as_slice()) {
+            (Some(selector), _) => ScheduledMessage::Message {
+                receiver,
+                selector,
+                list,
+            },
+            (None, []) => ScheduledMessage::Bang { receiver },
+            (None, [Atom::Float(value)]) => ScheduledMessage::Float {
+                receiver,
+                value: *value,
+            },
+            (None, [Atom::Symbol(symbol)]) => ScheduledMessage::Symbol {
+                receiver,
+                symbol: symbol.clone(),
+            },
+            (None, _) => ScheduledMessage::List { receiver, list },
+        })
+    }
+
+    /// Finds the receiver of an address, with the selector for the routes which end with `/*`.
+    fn receiver_for(&self, address: &str) -> Option<(String, Option<String>)> {
+        if let Some(route) = self
+            .routes
+            .iter()
+            .find(|route| !route.prefix && route.address == address)
+        {
+            return Some((route.receiver.clone(), None));
+        }
+        let prefixed = self
+            .routes
+            .iter()
+            .filter(|route| route.prefix)
+            .filter_map(|route| {
+                let rest = address.strip_prefix(&route.address)?.strip_prefix('/')?;
+                (!rest.is_empty()).then_some((route, rest))
+            })
+            .max_by_key(|(route, _)| route.address.len());
+        if let Some((route, rest)) = prefixed {
+            return Some((route.receiver.clone(), Some(rest.to_owned())));
+        }
+        if !self.default_routes {
+            return None;
+        }
+        let receiver = address.strip_prefix('/')?.replace('/', "_");
+        (!receiver.is_empty()).then_some((receiver, None))
+    }
+
+    /// Maps a message from pd to the message which is sent out.
+    fn osc_message(&self, message: &PdMessage) -> Option<OscMessage> {
+        let message = AddressedMessage::from_pd_message(message)?;
+        let args = message.atoms.iter().map(OscArg::from);
+        if matches!(
+            message.selector.as_str(),
+            "bang" | "float" | "symbol" | "list"
+        ) {
+            let address = self.address_for(&message.receiver)?;
+            return Some(OscMessage::new(address, args.collect()));
+        }
+        if let Some(route) = self
+            .routes
+            .iter()
+            .find(|route| route.prefix && route.receiver == message.receiver)
+        {
+            let address = format!("{}/{}", route.address, message.selector);
+            return Some(OscMessage::new(address, args.collect()));
+        }
+        let address = self.address_for(&message.receiver)?;
+        let args = core::iter::once(OscArg::String(message.selector))
+            .chain(message.atoms.iter().map(OscArg::from))
+            .collect();
+        Some(OscMessage::new(address, args))
+    }
+
+    /// Finds the address of a source, the reverse of [`receiver_for`](OscBridge::receiver_for).
+    fn address_for(&self, source: &str) -> Option<String> {
+        if let Some(route) = self
+            .routes
+            .iter()
+            .find(|route| !route.prefix && route.receiver == source)
+        {
+            return Some(route.address.clone());
+        }
+        self.default_routes
+            .then(|| format!("/{}", source.replace('_', "/")))
+    }
+}
+
+impl Drop for OscBridge {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn receive_packets(
+    socket: &UdpSocket,
+    sender: &mpsc::Sender<Result<OscPacket, OscError>>,
+    stop: &AtomicBool,
+) {
+    let mut buffer = vec![0_u8; MAX_DATAGRAM];
+    while !stop.load(Ordering::Relaxed) {
+        match socket.recv_from(&mut buffer) {
+            Ok((read, _)) => {
+                let packet = OscPacket::decode(buffer.get(..read).unwrap_or_default());
+                if sender.send(packet).is_err() {
+                    return;
+                }
+            }
+            Err(err) if is_timeout(&err) => {}
+            // Errors like the connection reset which Windows reports for an earlier packet to a closed port
+            // are about a single packet, the socket is read until the bridge is dropped.
+            Err(_) => thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+
+    use super::*;
+
+    #[test]
+    fn message_encoding() {
+        let packet = OscPacket::Message(OscMessage::new(
+            "/a",
+            vec![
+                OscArg::Int(1),
+                OscArg::Float(0.5),
+                OscArg::String("abcd".to_owned()),
+                OscArg::Blob(vec![7, 8]),
+            ],
+        ));
+        let bytes = packet.encode();
+        let expected: Vec<u8> = [
+            &b"/a\0\0"[..],
+            b",ifsb\0\0\0",
+            &[0, 0, 0, 1],
+            &0.5_f32.to_be_bytes(),
+            b"abcd\0\0\0\0",
+            &[0, 0, 0, 2, 7, 8, 0, 0],
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+        assert_eq!(OscPacket::decode(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn bundle_encoding() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let packet = OscPacket::Bundle(OscBundle {
+            timetag: OscTimeTag::from_system_time(time),
+            content: vec![
+                OscPacket::Message(OscMessage::new("/x", vec![])),
+                OscPacket::Bundle(OscBundle {
+                    timetag: OscTimeTag::IMMEDIATELY,
+                    content: vec![OscPacket::Message(OscMessage::new(
+                        "/y",
+                        vec![OscArg::Int(-3)],
+                    ))],
+                }),
+            ],
+        });
+        let decoded = OscPacket::decode(&packet.encode()).unwrap();
+        assert_eq!(decoded, packet);
+
+        let OscPacket::Bundle(bundle) = decoded else {
+            panic!("Expected a bundle");
+        };
+        let round_trip = bundle.timetag.to_system_time().unwrap();
+        let difference = round_trip
+            .duration_since(time)
+            .unwrap_or_else(|err| err.duration());
+        assert!(difference < Duration::from_micros(1));
+        assert_eq!(OscTimeTag::IMMEDIATELY.to_system_time(), None);
+    }
+
+    #[test]
+    fn decoding_errors() {
+        assert!(matches!(
+            OscPacket::decode(b"nope"),
+            Err(OscError::InvalidPacket)
+        ));
+        assert!(matches!(
+            OscPacket::decode(b"/a\0\0,i\0\0\0\0"),
+            Err(OscError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            OscPacket::decode(b"/a\0\0,d\0\0"),
+            Err(OscError::UnsupportedType('d'))
+        ));
+        // Type tags could be left out when there are no arguments.
+        assert_eq!(
+            OscPacket::decode(b"/a\0\0").unwrap(),
+            OscPacket::Message(OscMessage::new("/a", vec![]))
+        );
+    }
+}
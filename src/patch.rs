@@ -0,0 +1,589 @@
+use core::{fmt, str::FromStr};
+
+use crate::error::PatchParseError;
+
+mod builder;
+mod bundle;
+mod parse;
+mod write;
+
+pub(crate) use parse::float;
+
+pub use builder::{BoxId, PatchBuilder};
+pub use bundle::PatchBundle;
+
+/// A single word in a pd file record.
+///
+/// Escapes are resolved while parsing, so a `\$0-foo` in the file becomes `Symbol("$0-foo")`
+/// and they are added back while writing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    /// A number.
+    Float(f64),
+    /// A symbol with its escapes resolved.
+    Symbol(String),
+    /// An escaped comma, `\,` in the file.
+    Comma,
+    /// An escaped semicolon, `\;` in the file.
+    Semicolon,
+}
+
+impl Token {
+    /// Returns the inner string if the token is a symbol.
+    pub fn as_symbol(&self) -> Option<&str> {
+        match self {
+            Self::Symbol(symbol) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    /// Returns the inner value if the token is a float.
+    pub const fn as_float(&self) -> Option<f64> {
+        match *self {
+            Self::Float(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl From<f64> for Token {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<i32> for Token {
+    fn from(value: i32) -> Self {
+        Self::Float(value.into())
+    }
+}
+
+impl From<&str> for Token {
+    fn from(value: &str) -> Self {
+        Self::Symbol(value.to_owned())
+    }
+}
+
+impl From<String> for Token {
+    fn from(value: String) -> Self {
+        Self::Symbol(value)
+    }
+}
+
+/// A parsed pd file.
+///
+/// Parsing and writing back a file which is not modified reproduces it byte for byte.
+/// Records which are modified or added are written in the canonical form pd uses.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::patch::{Node, Patch, Token};
+///
+/// let source = "#N canvas 0 50 450 300 12;\n#X obj 30 27 osc~ 440;\n#X obj 30 60 dac~;\n#X connect 0 0 1 0;\n";
+/// let mut patch: Patch = source.parse().unwrap();
+/// assert_eq!(patch.to_string(), source);
+///
+/// if let Node::Object(osc) = &mut patch.canvas.elements[0].node {
+///     osc.args[1] = Token::Float(220.0);
+/// }
+/// assert!(patch.to_string().contains("#X obj 30 27 osc~ 220;"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Patch {
+    /// The top level canvas of the patch.
+    pub canvas: Canvas,
+    header: Option<Raw>,
+    trailing: String,
+}
+
+impl Patch {
+    /// Creates a patch from its top level canvas.
+    pub fn new(canvas: Canvas) -> Self {
+        Self {
+            canvas,
+            header: None,
+            trailing: String::from("\n"),
+        }
+    }
+
+    /// Parses the contents of a pd file.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`MissingRootCanvas`](crate::error::PatchParseError::MissingRootCanvas)
+    /// - [`UnterminatedRecord`](crate::error::PatchParseError::UnterminatedRecord)
+    /// - [`InvalidRecord`](crate::error::PatchParseError::InvalidRecord)
+    /// - [`UnbalancedRestore`](crate::error::PatchParseError::UnbalancedRestore)
+    /// - [`UnclosedSubpatch`](crate::error::PatchParseError::UnclosedSubpatch)
+    pub fn parse(source: &str) -> Result<Self, PatchParseError> {
+        parse::parse(source)
+    }
+}
+
+// Formatting details of the source do not make two patches different.
+impl PartialEq for Patch {
+    fn eq(&self, other: &Self) -> bool {
+        self.canvas == other.canvas
+    }
+}
+
+impl FromStr for Patch {
+    type Err = PatchParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&write::write(self))
+    }
+}
+
+/// A canvas, either the top level one of the patch or the one of a subpatch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    /// The horizontal position of the window.
+    pub x: i32,
+    /// The vertical position of the window.
+    pub y: i32,
+    /// The width of the window.
+    pub width: i32,
+    /// The height of the window.
+    pub height: i32,
+    /// What kind of canvas this is.
+    pub kind: CanvasKind,
+    /// The contents of the canvas in the order they are written.
+    pub elements: Vec<Element>,
+}
+
+impl Canvas {
+    /// Creates an empty top level canvas with the defaults of pd.
+    pub const fn new() -> Self {
+        Self {
+            x: 0,
+            y: 50,
+            width: 450,
+            height: 300,
+            kind: CanvasKind::Root { font_size: 12 },
+            elements: Vec::new(),
+        }
+    }
+
+    /// Iterates the elements which are boxes in the order pd indexes them for connections.
+    pub fn boxes(&self) -> impl Iterator<Item = &Element> {
+        self.elements.iter().filter(|element| element.node.is_box())
+    }
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The kind of a canvas, the header of the canvas differs between them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CanvasKind {
+    /// The top level canvas of a patch, `#N canvas x y width height font_size;`.
+    Root {
+        /// The font size of the patch.
+        font_size: i32,
+    },
+    /// The canvas of a subpatch or a graph, `#N canvas x y width height name open;`.
+    Subpatch {
+        /// The name of the subpatch, `(subpatch)` for graphs.
+        name: String,
+        /// Whether the window of the subpatch is open when the patch is loaded.
+        open: bool,
+    },
+}
+
+/// An element of a canvas, which may span more than one record in the file.
+#[derive(Debug, Clone)]
+pub struct Element {
+    /// The contents of the element.
+    pub node: Node,
+    raws: Vec<Raw>,
+}
+
+impl Element {
+    /// Creates an element which is written in the canonical form.
+    pub const fn new(node: Node) -> Self {
+        Self {
+            node,
+            raws: Vec::new(),
+        }
+    }
+}
+
+// Formatting details of the source do not make two elements different.
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl From<Node> for Element {
+    fn from(node: Node) -> Self {
+        Self::new(node)
+    }
+}
+
+/// The contents of an element.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Node {
+    /// An object box, `#X obj`.
+    Object(Object),
+    /// A message box, `#X msg`.
+    Message(Message),
+    /// A comment, `#X text`.
+    Comment(Comment),
+    /// A gatom, `#X floatatom`, `#X symbolatom` or `#X listatom`.
+    AtomBox(AtomBox),
+    /// A subpatch or a graph, from `#N canvas` to `#X restore`.
+    Subpatch(Subpatch),
+    /// An array in a graph, `#X array` followed by its `#A` data.
+    Array(Array),
+    /// A connection between two boxes, `#X connect`.
+    Connect(Connect),
+    /// The coordinates of a graph or a graph on parent canvas, `#X coords`.
+    Coords(Vec<Token>),
+    /// Any other record, with its leading `#N`, `#X` or `#A` and the selector.
+    Other(Vec<Token>),
+}
+
+impl Node {
+    /// Checks if the node is a box which pd counts while indexing connections.
+    pub const fn is_box(&self) -> bool {
+        matches!(
+            self,
+            Self::Object(_)
+                | Self::Message(_)
+                | Self::Comment(_)
+                | Self::AtomBox(_)
+                | Self::Subpatch(_)
+                | Self::Array(_)
+        )
+    }
+}
+
+/// An object box, `#X obj x y class args..;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    /// The horizontal position.
+    pub x: i32,
+    /// The vertical position.
+    pub y: i32,
+    /// The class name followed by the creation arguments, empty for an empty box.
+    pub args: Vec<Token>,
+    /// The width of the box in characters, `, f width` in the file.
+    pub width: Option<i32>,
+}
+
+impl Object {
+    /// Returns the class name of the object.
+    pub fn class(&self) -> Option<&str> {
+        self.args.first().and_then(Token::as_symbol)
+    }
+}
+
+/// A message box, `#X msg x y contents..;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// The horizontal position.
+    pub x: i32,
+    /// The vertical position.
+    pub y: i32,
+    /// The contents of the message.
+    pub args: Vec<Token>,
+    /// The width of the box in characters, `, f width` in the file.
+    pub width: Option<i32>,
+}
+
+/// A comment, `#X text x y contents..;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The horizontal position.
+    pub x: i32,
+    /// The vertical position.
+    pub y: i32,
+    /// The contents of the comment.
+    pub args: Vec<Token>,
+    /// The width of the box in characters, `, f width` in the file.
+    pub width: Option<i32>,
+}
+
+/// The kind of a gatom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtomBoxKind {
+    /// `#X floatatom`.
+    Float,
+    /// `#X symbolatom`.
+    Symbol,
+    /// `#X listatom`.
+    List,
+}
+
+impl AtomBoxKind {
+    pub(crate) const fn selector(self) -> &'static str {
+        match self {
+            Self::Float => "floatatom",
+            Self::Symbol => "symbolatom",
+            Self::List => "listatom",
+        }
+    }
+}
+
+/// A gatom, `#X floatatom x y width min max label_position label receive send..;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtomBox {
+    /// The kind of the gatom.
+    pub kind: AtomBoxKind,
+    /// The horizontal position.
+    pub x: i32,
+    /// The vertical position.
+    pub y: i32,
+    /// The width, range, label and send and receive names as they are in the file.
+    pub args: Vec<Token>,
+}
+
+/// A subpatch or a graph.
+///
+/// In the file, the canvas comes first and `#X restore x y args..;` closes it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subpatch {
+    /// The canvas of the subpatch.
+    pub canvas: Canvas,
+    /// The horizontal position of the box in the parent canvas.
+    pub x: i32,
+    /// The vertical position of the box in the parent canvas.
+    pub y: i32,
+    /// The contents of the box in the parent canvas, e.g. `pd name` or `graph`.
+    pub args: Vec<Token>,
+    /// The width of the box in characters, `, f width` in the file.
+    pub width: Option<i32>,
+}
+
+/// An array in a graph, `#X array name size type flags;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array {
+    /// The name of the array.
+    pub name: String,
+    /// The size of the array.
+    pub size: usize,
+    /// The element type of the array, `float` for the arrays pd creates.
+    pub element_type: String,
+    /// The save and plot flags of the array.
+    pub flags: i32,
+    /// The saved contents of the array, `#A start values..;` in the file.
+    pub data: Vec<ArrayData>,
+}
+
+/// A chunk of the saved contents of an array, `#A start values..;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayData {
+    /// The index of the first value.
+    pub start: usize,
+    /// The values.
+    pub values: Vec<f64>,
+}
+
+/// A connection, `#X connect source outlet sink inlet;`.
+///
+/// Boxes are indexed in the order they appear in their canvas, see [`Canvas::boxes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Connect {
+    /// The index of the box the connection starts from.
+    pub source: usize,
+    /// The outlet of the source box.
+    pub outlet: usize,
+    /// The index of the box the connection ends at.
+    pub sink: usize,
+    /// The inlet of the sink box.
+    pub inlet: usize,
+}
+
+/// The original text of a record which is kept to write unmodified records back as they are.
+#[derive(Debug, Clone)]
+struct Raw {
+    /// The whitespace before the record.
+    leading: String,
+    /// The record including the terminating `;`.
+    text: String,
+    /// The canonical form of the record when it was parsed.
+    canonical: String,
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+    use super::*;
+
+    const EVERYTHING: &str = r"#N canvas 691 232 912 210 12;
+#X obj 29 18 r float_from_rust;
+#X obj 29 54 s \$0-float;
+#X msg 162 18 1 \, 2 \; target bang, f 12;
+#X text 300 10 a comment with escaped \, comma and a dollar \$1;
+#X floatatom 24 63 5 0 0 0 - - - 0;
+#N canvas 0 50 450 300 inner 0;
+#X obj 10 10 inlet;
+#X obj 10 40 outlet;
+#X connect 0 0 1 0;
+#X restore 162 100 pd inner;
+#N canvas 0 50 450 250 (subpatch) 0;
+#X array table 4 float 3;
+#A 0 0.1 -0.5 1e-05
+0 1;
+#X coords 0 1 4 -1 200 140 1 0 0;
+#X restore 300 100 graph;
+#X obj 10 200 osc~ 440, f 10;
+#X connect 0 0 1 0;
+#X connect 2 0 6 0;
+";
+
+    #[test]
+    fn round_trip() {
+        let patch: Patch = EVERYTHING.parse().unwrap();
+        assert_eq!(patch.to_string(), EVERYTHING);
+
+        // Different formatting is kept as well.
+        let odd = "#N canvas 0 50 450 300 12;\r\n\r\n#X obj  10 10\n  osc~ 440 ;  \n";
+        assert_eq!(Patch::parse(odd).unwrap().to_string(), odd);
+    }
+
+    #[test]
+    fn typed_nodes() {
+        let patch: Patch = EVERYTHING.parse().unwrap();
+        let elements = &patch.canvas.elements;
+
+        assert!(matches!(
+            patch.canvas.kind,
+            CanvasKind::Root { font_size: 12 }
+        ));
+        assert_eq!(patch.canvas.boxes().count(), 8);
+
+        let Node::Object(send) = &elements[1].node else {
+            panic!("not an object");
+        };
+        assert_eq!(send.class(), Some("s"));
+        assert_eq!(send.args[1], Token::Symbol("$0-float".to_owned()));
+
+        let Node::Message(message) = &elements[2].node else {
+            panic!("not a message");
+        };
+        assert_eq!(
+            message.args,
+            vec![
+                Token::Float(1.0),
+                Token::Comma,
+                Token::Float(2.0),
+                Token::Semicolon,
+                Token::from("target"),
+                Token::from("bang"),
+            ]
+        );
+        assert_eq!(message.width, Some(12));
+
+        let Node::Subpatch(inner) = &elements[5].node else {
+            panic!("not a subpatch");
+        };
+        assert!(matches!(
+            &inner.canvas.kind,
+            CanvasKind::Subpatch { name, open: false } if name == "inner"
+        ));
+        assert_eq!(inner.canvas.elements.len(), 3);
+
+        let Node::Subpatch(graph) = &elements[6].node else {
+            panic!("not a graph");
+        };
+        let Node::Array(array) = &graph.canvas.elements[0].node else {
+            panic!("not an array");
+        };
+        assert_eq!(array.name, "table");
+        assert_eq!(array.size, 4);
+        assert_eq!(array.data[0].values, vec![0.1, -0.5, 1e-05, 0.0, 1.0]);
+
+        assert_eq!(
+            elements[9].node,
+            Node::Connect(Connect {
+                source: 2,
+                outlet: 0,
+                sink: 6,
+                inlet: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn modified_records_are_canonical() {
+        let mut patch: Patch = EVERYTHING.parse().unwrap();
+
+        if let Node::Object(object) = &mut patch.canvas.elements[0].node {
+            object.args[1] = Token::from("$0-other name");
+        }
+        patch
+            .canvas
+            .elements
+            .push(Element::new(Node::Connect(Connect {
+                source: 0,
+                outlet: 0,
+                sink: 7,
+                inlet: 1,
+            })));
+
+        let written = patch.to_string();
+        assert!(written.contains("\n#X obj 29 18 r \\$0-other\\ name;\n"));
+        assert!(written.ends_with("#X connect 2 0 6 0;\n#X connect 0 0 7 1;\n"));
+        // The rest is untouched.
+        assert!(written.contains("#A 0 0.1 -0.5 1e-05\n0 1;"));
+
+        // What is written parses back to the same patch.
+        assert_eq!(Patch::parse(&written).unwrap(), patch);
+    }
+
+    #[test]
+    fn new_patch() {
+        let mut canvas = Canvas::new();
+        canvas.elements.push(
+            Node::Object(Object {
+                x: 10,
+                y: 10,
+                args: vec![Token::from("osc~"), Token::from(440)],
+                width: None,
+            })
+            .into(),
+        );
+        assert_eq!(
+            Patch::new(canvas).to_string(),
+            "#N canvas 0 50 450 300 12;\n#X obj 10 10 osc~ 440;\n"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Patch::parse("#X obj 10 10 osc~;\n"),
+            Err(PatchParseError::MissingRootCanvas)
+        ));
+        assert!(matches!(
+            Patch::parse("#N canvas 0 50 450 300 12;\n#X obj 10 10 osc~"),
+            Err(PatchParseError::UnterminatedRecord { line: 2 })
+        ));
+        assert!(matches!(
+            Patch::parse("#N canvas 0 50 450 300 12;\n#X restore 0 0 pd a;\n"),
+            Err(PatchParseError::UnbalancedRestore { line: 2 })
+        ));
+        assert!(matches!(
+            Patch::parse("#N canvas 0 50 450 300 12;\n#N canvas 0 50 450 300 a 0;\n"),
+            Err(PatchParseError::UnclosedSubpatch)
+        ));
+        assert!(matches!(
+            Patch::parse("#N canvas 0 50 450 300 12;\n#X connect 0 a 1 0;\n"),
+            Err(PatchParseError::InvalidRecord { line: 2, .. })
+        ));
+    }
+}
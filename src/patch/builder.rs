@@ -0,0 +1,323 @@
+use super::{
+    parse, Canvas, CanvasKind, Comment, Connect, Element, Message, Node, Object, Patch, Subpatch,
+    Token,
+};
+
+/// The position of the first box which is laid out automatically.
+const LAYOUT_ORIGIN: (i32, i32) = (20, 20);
+/// The vertical distance between the boxes which are laid out automatically.
+const LAYOUT_ROW_HEIGHT: i32 = 30;
+
+/// The index of a box in the canvas it is added to, used for connecting boxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BoxId(usize);
+
+impl BoxId {
+    /// Returns the index pd uses for the box in `#X connect` records.
+    pub const fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Builds a [`Patch`] in code, which could be written to text for [`Pd::eval_patch`](crate::Pd::eval_patch).
+///
+/// Boxes are indexed in the order they are added and laid out in a column
+/// unless they are added with a position.
+/// The contents of a box is written as it would be typed in pd,
+/// so `,` and `;` in a message box separate messages.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::patch::PatchBuilder;
+///
+/// let mut builder = PatchBuilder::new();
+/// let osc = builder.obj("osc~ 440");
+/// let gain = builder.obj("*~ 0.1");
+/// let dac = builder.obj("dac~");
+/// builder
+///     .connect(osc, 0, gain, 0)
+///     .connect(gain, 0, dac, 0)
+///     .connect(gain, 0, dac, 1);
+///
+/// assert_eq!(
+///     builder.build().to_string(),
+///     "#N canvas 0 50 450 300 12;
+/// #X obj 20 20 osc~ 440;
+/// #X obj 20 50 *~ 0.1;
+/// #X obj 20 80 dac~;
+/// #X connect 0 0 1 0;
+/// #X connect 1 0 2 0;
+/// #X connect 1 0 2 1;
+/// "
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatchBuilder {
+    canvas: Canvas,
+    connections: Vec<Connect>,
+    box_count: usize,
+    next_row: i32,
+}
+
+impl PatchBuilder {
+    /// Creates a builder for an empty patch with the default window of pd.
+    pub const fn new() -> Self {
+        Self::with_canvas(Canvas::new())
+    }
+
+    const fn with_canvas(canvas: Canvas) -> Self {
+        Self {
+            canvas,
+            connections: Vec::new(),
+            box_count: 0,
+            next_row: 0,
+        }
+    }
+
+    /// Sets the position and the size of the window of the canvas.
+    pub fn window(&mut self, x: i32, y: i32, width: i32, height: i32) -> &mut Self {
+        self.canvas.x = x;
+        self.canvas.y = y;
+        self.canvas.width = width;
+        self.canvas.height = height;
+        self
+    }
+
+    /// Sets the font size of the patch, it has no effect for subpatches.
+    pub fn font_size(&mut self, size: i32) -> &mut Self {
+        if let CanvasKind::Root { font_size } = &mut self.canvas.kind {
+            *font_size = size;
+        }
+        self
+    }
+
+    /// Adds an object box, e.g. `osc~ 440`, below the previous box.
+    pub fn obj(&mut self, text: &str) -> BoxId {
+        let (x, y) = self.next_position();
+        self.obj_at(text, x, y)
+    }
+
+    /// Adds an object box at the given position.
+    pub fn obj_at(&mut self, text: &str, x: i32, y: i32) -> BoxId {
+        self.add(Node::Object(Object {
+            x,
+            y,
+            args: words(text),
+            width: None,
+        }))
+    }
+
+    /// Adds a message box, e.g. `; pd dsp 1`, below the previous box.
+    pub fn msg(&mut self, text: &str) -> BoxId {
+        let (x, y) = self.next_position();
+        self.msg_at(text, x, y)
+    }
+
+    /// Adds a message box at the given position.
+    pub fn msg_at(&mut self, text: &str, x: i32, y: i32) -> BoxId {
+        self.add(Node::Message(Message {
+            x,
+            y,
+            args: words(text),
+            width: None,
+        }))
+    }
+
+    /// Adds a comment below the previous box, comments are indexed like the other boxes.
+    pub fn comment(&mut self, text: &str) -> BoxId {
+        let (x, y) = self.next_position();
+        self.comment_at(text, x, y)
+    }
+
+    /// Adds a comment at the given position.
+    pub fn comment_at(&mut self, text: &str, x: i32, y: i32) -> BoxId {
+        self.add(Node::Comment(Comment {
+            x,
+            y,
+            args: words(text),
+            width: None,
+        }))
+    }
+
+    /// Adds a `pd name` subpatch below the previous box, its contents are built with the given closure.
+    ///
+    /// Boxes of the subpatch are indexed separately,
+    /// the ids returned from the inner builder can only be connected in the subpatch.
+    ///
+    /// # Example
+    /// ```rust
+    /// use libpd_rs::patch::PatchBuilder;
+    ///
+    /// let mut builder = PatchBuilder::new();
+    /// let source = builder.obj("osc~ 220");
+    /// let filter = builder.subpatch("filter", |inner| {
+    ///     let inlet = inner.obj("inlet~");
+    ///     let lop = inner.obj("lop~ 1000");
+    ///     let outlet = inner.obj("outlet~");
+    ///     inner.connect(inlet, 0, lop, 0).connect(lop, 0, outlet, 0);
+    /// });
+    /// builder.connect(source, 0, filter, 0);
+    /// ```
+    pub fn subpatch<F: FnOnce(&mut Self)>(&mut self, name: &str, build: F) -> BoxId {
+        let (x, y) = self.next_position();
+        self.subpatch_at(name, x, y, build)
+    }
+
+    /// Adds a `pd name` subpatch at the given position.
+    pub fn subpatch_at<F: FnOnce(&mut Self)>(
+        &mut self,
+        name: &str,
+        x: i32,
+        y: i32,
+        build: F,
+    ) -> BoxId {
+        let mut inner = Self::with_canvas(Canvas {
+            kind: CanvasKind::Subpatch {
+                name: name.to_owned(),
+                open: false,
+            },
+            ..Canvas::new()
+        });
+        build(&mut inner);
+
+        self.add(Node::Subpatch(Subpatch {
+            canvas: inner.into_canvas(),
+            x,
+            y,
+            args: vec![Token::from("pd"), Token::from(name)],
+            width: None,
+        }))
+    }
+
+    /// Connects an outlet of a box to an inlet of another one.
+    pub fn connect(
+        &mut self,
+        source: BoxId,
+        outlet: usize,
+        sink: BoxId,
+        inlet: usize,
+    ) -> &mut Self {
+        self.connections.push(Connect {
+            source: source.index(),
+            outlet,
+            sink: sink.index(),
+            inlet,
+        });
+        self
+    }
+
+    /// Builds the patch, connections are written after every box.
+    pub fn build(&self) -> Patch {
+        Patch::new(self.clone().into_canvas())
+    }
+
+    fn into_canvas(self) -> Canvas {
+        let mut canvas = self.canvas;
+        canvas.elements.extend(
+            self.connections
+                .into_iter()
+                .map(|connect| Element::new(Node::Connect(connect))),
+        );
+        canvas
+    }
+
+    fn add(&mut self, node: Node) -> BoxId {
+        let id = BoxId(self.box_count);
+        self.box_count += 1;
+        self.canvas.elements.push(Element::new(node));
+        id
+    }
+
+    fn next_position(&mut self) -> (i32, i32) {
+        let (x, y) = LAYOUT_ORIGIN;
+        let position = (x, y + self.next_row * LAYOUT_ROW_HEIGHT);
+        self.next_row += 1;
+        position
+    }
+}
+
+impl Default for PatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits the contents of a box to tokens, `,` and `;` are separators like they are in pd.
+fn words(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        let mut rest = word;
+        while let Some(position) = rest.find([',', ';']) {
+            let (before, after) = rest.split_at(position);
+            push_word(&mut tokens, before);
+            let mut after = after.chars();
+            tokens.push(if after.next() == Some(',') {
+                Token::Comma
+            } else {
+                Token::Semicolon
+            });
+            rest = after.as_str();
+        }
+        push_word(&mut tokens, rest);
+    }
+    tokens
+}
+
+fn push_word(tokens: &mut Vec<Token>, word: &str) {
+    if !word.is_empty() {
+        tokens.push(parse::float(word).map_or_else(|| Token::from(word), Token::Float));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+    use super::*;
+
+    #[test]
+    fn builds_text_for_eval_patch() {
+        let mut builder = PatchBuilder::new();
+        builder.window(10, 20, 300, 200).font_size(10);
+        let load = builder.obj("loadbang");
+        let msg = builder.msg("1 8, 0 0 10; pd dsp 1");
+        let filter = builder.subpatch("filter", |inner| {
+            let inlet = inner.obj("inlet");
+            let outlet = inner.obj_at("outlet", 100, 100);
+            inner.connect(inlet, 0, outlet, 0);
+        });
+        let comment = builder.comment_at("$0-name is escaped", 200, 20);
+        builder.connect(load, 0, msg, 0).connect(msg, 0, filter, 0);
+
+        assert_eq!(comment.index(), 3);
+        assert_eq!(
+            builder.build().to_string(),
+            "#N canvas 10 20 300 200 10;
+#X obj 20 20 loadbang;
+#X msg 20 50 1 8 \\, 0 0 10 \\; pd dsp 1;
+#N canvas 0 50 450 300 filter 0;
+#X obj 20 20 inlet;
+#X obj 100 100 outlet;
+#X connect 0 0 1 0;
+#X restore 20 80 pd filter;
+#X text 200 20 \\$0-name is escaped;
+#X connect 0 0 1 0;
+#X connect 1 0 2 0;
+"
+        );
+    }
+
+    #[test]
+    fn splits_words() {
+        assert_eq!(
+            words("  a,b ;1e3 -  "),
+            vec![
+                Token::from("a"),
+                Token::Comma,
+                Token::from("b"),
+                Token::Semicolon,
+                Token::Float(1000.0),
+                Token::from("-"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::error::PatchLifeCycleError;
+
+/// A set of patches, abstractions and other files which are kept in memory,
+/// usually embedded in the binary with [`include_patch_bundle`](crate::include_patch_bundle).
+///
+/// Paths in the bundle are relative to its root.
+/// A bundle is opened with [`Pd::open_bundle`](crate::Pd::open_bundle),
+/// which makes its abstractions and other files, e.g. samples, available to pd through its search paths.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::patch::PatchBundle;
+///
+/// let bundle = PatchBundle::new()
+///     .with_file("main.pd", "#N canvas 0 50 450 300 12;\n#X obj 20 20 voice 440;\n")
+///     .with_file("voice.pd", "#N canvas 0 50 450 300 12;\n#X obj 20 20 osc~ \\$1;\n");
+///
+/// assert!(bundle.contains("voice.pd"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatchBundle {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl PatchBundle {
+    /// Creates an empty bundle.
+    pub const fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a file to the bundle, replacing the file with the same path.
+    #[must_use]
+    pub fn with_file<P: AsRef<Path>, C: Into<Vec<u8>>>(mut self, path: P, contents: C) -> Self {
+        self.insert(path, contents);
+        self
+    }
+
+    /// Adds a file to the bundle, returning the contents of the file it replaces.
+    pub fn insert<P: AsRef<Path>, C: Into<Vec<u8>>>(
+        &mut self,
+        path: P,
+        contents: C,
+    ) -> Option<Vec<u8>> {
+        self.files
+            .insert(path.as_ref().to_path_buf(), contents.into())
+    }
+
+    /// Gets the contents of a file in the bundle.
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&[u8]> {
+        self.files.get(path.as_ref()).map(Vec::as_slice)
+    }
+
+    /// Checks if the bundle has a file with the given path.
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.files.contains_key(path.as_ref())
+    }
+
+    /// Iterates the files of the bundle ordered by their paths.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &[u8])> {
+        self.files
+            .iter()
+            .map(|(path, contents)| (path.as_path(), contents.as_slice()))
+    }
+
+    /// Returns the number of files in the bundle.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Checks if the bundle has no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Writes every file of the bundle to the directory, creating the subdirectories of their paths.
+    pub(crate) fn write_to(&self, directory: &Path) -> Result<(), PatchLifeCycleError> {
+        self.check_paths()?;
+        for (path, contents) in &self.files {
+            let failed = |msg: String| PatchLifeCycleError::FailedToWriteBundle {
+                path: path.to_string_lossy().to_string(),
+                msg,
+            };
+            let target = directory.join(path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|err| failed(err.to_string()))?;
+            }
+            fs::write(&target, contents).map_err(|err| failed(err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every path of the bundle stays inside of it.
+    pub(crate) fn check_paths(&self) -> Result<(), PatchLifeCycleError> {
+        for path in self.files.keys() {
+            if !path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+            {
+                return Err(PatchLifeCycleError::InvalidPathInBundle(
+                    path.to_string_lossy().to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: AsRef<Path>, C: Into<Vec<u8>>> FromIterator<(P, C)> for PatchBundle {
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        let mut bundle = Self::new();
+        for (path, contents) in iter {
+            bundle.insert(path, contents);
+        }
+        bundle
+    }
+}
+
+/// Embeds files of a directory in the binary as a [`PatchBundle`](crate::patch::PatchBundle).
+///
+/// The directory is relative to the file the macro is called in, like it is for [`include_bytes`].
+/// The files keep their paths relative to the directory in the bundle.
+///
+/// # Example
+/// ```ignore
+/// use libpd_rs::{include_patch_bundle, Pd};
+///
+/// let bundle = include_patch_bundle!("../patches", ["main.pd", "voice.pd", "samples/kick.wav"]);
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_bundle(&bundle, "main.pd").unwrap();
+/// ```
+#[macro_export]
+macro_rules! include_patch_bundle {
+    ($directory:literal, [$($file:literal),* $(,)?]) => {
+        $crate::patch::PatchBundle::new()
+            $(.with_file($file, include_bytes!(concat!($directory, "/", $file)).as_slice()))*
+    };
+}
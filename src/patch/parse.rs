@@ -0,0 +1,450 @@
+use super::{
+    write, Array, ArrayData, AtomBox, AtomBoxKind, Canvas, CanvasKind, Comment, Connect, Element,
+    Message, Node, Object, Patch, Raw, Subpatch, Token,
+};
+use crate::error::PatchParseError;
+
+/// A record as it is in the source, terminated with `;`.
+struct SourceRecord<'a> {
+    leading: &'a str,
+    text: &'a str,
+    line: usize,
+}
+
+impl SourceRecord<'_> {
+    fn raw(&self, canonical: String) -> Raw {
+        Raw {
+            leading: self.leading.to_owned(),
+            text: self.text.to_owned(),
+            canonical,
+        }
+    }
+
+    fn invalid(&self) -> PatchParseError {
+        PatchParseError::InvalidRecord {
+            line: self.line,
+            record: self.text.to_owned(),
+        }
+    }
+}
+
+/// A word of a record, unescaped commas are only meaningful for the width of boxes.
+enum Lexeme {
+    Token(Token),
+    BareComma,
+}
+
+/// A canvas which is being parsed with the raw text of its header.
+struct Frame {
+    canvas: Canvas,
+    header: Raw,
+}
+
+pub(super) fn parse(source: &str) -> Result<Patch, PatchParseError> {
+    let (records, trailing) = split_records(source)?;
+    let mut records = records.into_iter();
+
+    let first = records.next().ok_or(PatchParseError::MissingRootCanvas)?;
+    let root = match lex(first.text).as_slice() {
+        [Lexeme::Token(Token::Symbol(head)), Lexeme::Token(Token::Symbol(selector)), args @ ..]
+            if head == "#N" && selector == "canvas" =>
+        {
+            parse_canvas(&tokens(args), &first)?
+        }
+        _ => return Err(PatchParseError::MissingRootCanvas),
+    };
+    let mut stack = vec![Frame {
+        header: first.raw(write::canvas_header(&root)),
+        canvas: root,
+    }];
+
+    for record in records {
+        let lexemes = lex(record.text);
+        let (head, selector, args) = match lexemes.as_slice() {
+            [Lexeme::Token(Token::Symbol(head)), Lexeme::Token(Token::Symbol(selector)), args @ ..] => {
+                (head.as_str(), selector.as_str(), args)
+            }
+            [Lexeme::Token(Token::Symbol(head)), args @ ..] => (head.as_str(), "", args),
+            _ => ("", "", lexemes.as_slice()),
+        };
+
+        match (head, selector) {
+            ("#N", "canvas") => {
+                let canvas = parse_canvas(&tokens(args), &record)?;
+                stack.push(Frame {
+                    header: record.raw(write::canvas_header(&canvas)),
+                    canvas,
+                });
+            }
+            ("#X", "restore") => {
+                if stack.len() < 2 {
+                    return Err(PatchParseError::UnbalancedRestore { line: record.line });
+                }
+                let frame = stack.pop().ok_or(PatchParseError::UnclosedSubpatch)?;
+                let (tokens, width) = tokens_with_width(args);
+                let [x, y, args @ ..] = tokens.as_slice() else {
+                    return Err(record.invalid());
+                };
+                let subpatch = Subpatch {
+                    canvas: frame.canvas,
+                    x: int(x).ok_or_else(|| record.invalid())?,
+                    y: int(y).ok_or_else(|| record.invalid())?,
+                    args: args.to_vec(),
+                    width,
+                };
+                let restore = record.raw(write::restore(&subpatch));
+                push(
+                    &mut stack,
+                    Element {
+                        node: Node::Subpatch(subpatch),
+                        raws: vec![frame.header, restore],
+                    },
+                )?;
+            }
+            ("#A", _) => {
+                // Array data belongs to the array right before it.
+                let follows_array = stack
+                    .last()
+                    .and_then(|frame| frame.canvas.elements.last())
+                    .is_some_and(|element| matches!(element.node, Node::Array(_)));
+                match array_data(&tokens(args)).filter(|_| follows_array) {
+                    Some(data) => append_array_data(&mut stack, data, &record),
+                    None => push_single(&mut stack, Node::Other(tokens(&lexemes)), &record)?,
+                }
+            }
+            ("#X", _) => {
+                let node = parse_single(selector, args, &record)?
+                    .unwrap_or_else(|| Node::Other(tokens(&lexemes)));
+                if let Node::Array(array) = &node {
+                    let raw = record.raw(write::array_header(array));
+                    push(
+                        &mut stack,
+                        Element {
+                            node,
+                            raws: vec![raw],
+                        },
+                    )?;
+                } else {
+                    push_single(&mut stack, node, &record)?;
+                }
+            }
+            _ => push_single(&mut stack, Node::Other(tokens(&lexemes)), &record)?,
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(PatchParseError::UnclosedSubpatch);
+    }
+    let root = stack.pop().ok_or(PatchParseError::MissingRootCanvas)?;
+
+    Ok(Patch {
+        canvas: root.canvas,
+        header: Some(root.header),
+        trailing: trailing.to_owned(),
+    })
+}
+
+/// Parses the `#X` records which are a single record, `None` for the ones which are not typed.
+fn parse_single(
+    selector: &str,
+    args: &[Lexeme],
+    record: &SourceRecord<'_>,
+) -> Result<Option<Node>, PatchParseError> {
+    let node = match selector {
+        "obj" => {
+            let (x, y, args, width) = positioned(args, record)?;
+            Node::Object(Object { x, y, args, width })
+        }
+        "msg" => {
+            let (x, y, args, width) = positioned(args, record)?;
+            Node::Message(Message { x, y, args, width })
+        }
+        "text" => {
+            let (x, y, args, width) = positioned(args, record)?;
+            Node::Comment(Comment { x, y, args, width })
+        }
+        "floatatom" | "symbolatom" | "listatom" => {
+            let kind = match selector {
+                "floatatom" => AtomBoxKind::Float,
+                "symbolatom" => AtomBoxKind::Symbol,
+                _ => AtomBoxKind::List,
+            };
+            let tokens = tokens(args);
+            let [x, y, args @ ..] = tokens.as_slice() else {
+                return Err(record.invalid());
+            };
+            Node::AtomBox(AtomBox {
+                kind,
+                x: int(x).ok_or_else(|| record.invalid())?,
+                y: int(y).ok_or_else(|| record.invalid())?,
+                args: args.to_vec(),
+            })
+        }
+        "connect" => {
+            let tokens = tokens(args);
+            let [source, outlet, sink, inlet] = tokens.as_slice() else {
+                return Err(record.invalid());
+            };
+            let to_index = |token: &Token| index(token).ok_or_else(|| record.invalid());
+            Node::Connect(Connect {
+                source: to_index(source)?,
+                outlet: to_index(outlet)?,
+                sink: to_index(sink)?,
+                inlet: to_index(inlet)?,
+            })
+        }
+        "coords" => Node::Coords(tokens(args)),
+        "array" => {
+            let tokens = tokens(args);
+            let [Token::Symbol(name), size, Token::Symbol(element_type), flags] = tokens.as_slice()
+            else {
+                return Err(record.invalid());
+            };
+            Node::Array(Array {
+                name: name.clone(),
+                size: index(size).ok_or_else(|| record.invalid())?,
+                element_type: element_type.clone(),
+                flags: int(flags).ok_or_else(|| record.invalid())?,
+                data: Vec::new(),
+            })
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(node))
+}
+
+/// The position, the contents and the width of a box.
+type Positioned = (i32, i32, Vec<Token>, Option<i32>);
+
+fn positioned(args: &[Lexeme], record: &SourceRecord<'_>) -> Result<Positioned, PatchParseError> {
+    let (tokens, width) = tokens_with_width(args);
+    match tokens.as_slice() {
+        [x, y, rest @ ..] => Ok((
+            int(x).ok_or_else(|| record.invalid())?,
+            int(y).ok_or_else(|| record.invalid())?,
+            rest.to_vec(),
+            width,
+        )),
+        _ => Err(record.invalid()),
+    }
+}
+
+fn parse_canvas(args: &[Token], record: &SourceRecord<'_>) -> Result<Canvas, PatchParseError> {
+    let (x, y, width, height, kind) = match args {
+        [x, y, width, height, font_size] => (
+            x,
+            y,
+            width,
+            height,
+            CanvasKind::Root {
+                font_size: int(font_size).ok_or_else(|| record.invalid())?,
+            },
+        ),
+        [x, y, width, height, Token::Symbol(name), open] => (
+            x,
+            y,
+            width,
+            height,
+            CanvasKind::Subpatch {
+                name: name.clone(),
+                open: int(open).ok_or_else(|| record.invalid())? != 0,
+            },
+        ),
+        _ => return Err(record.invalid()),
+    };
+
+    Ok(Canvas {
+        x: int(x).ok_or_else(|| record.invalid())?,
+        y: int(y).ok_or_else(|| record.invalid())?,
+        width: int(width).ok_or_else(|| record.invalid())?,
+        height: int(height).ok_or_else(|| record.invalid())?,
+        kind,
+        elements: Vec::new(),
+    })
+}
+
+fn array_data(args: &[Token]) -> Option<ArrayData> {
+    let (start, values) = args.split_first()?;
+    Some(ArrayData {
+        start: index(start)?,
+        values: values
+            .iter()
+            .map(Token::as_float)
+            .collect::<Option<Vec<f64>>>()?,
+    })
+}
+
+fn append_array_data(stack: &mut [Frame], data: ArrayData, record: &SourceRecord<'_>) {
+    if let Some(Element {
+        node: Node::Array(array),
+        raws,
+    }) = stack
+        .last_mut()
+        .and_then(|frame| frame.canvas.elements.last_mut())
+    {
+        raws.push(record.raw(write::array_data(&data)));
+        array.data.push(data);
+    }
+}
+
+fn push(stack: &mut [Frame], element: Element) -> Result<(), PatchParseError> {
+    let frame = stack.last_mut().ok_or(PatchParseError::MissingRootCanvas)?;
+    frame.canvas.elements.push(element);
+    Ok(())
+}
+
+fn push_single(
+    stack: &mut [Frame],
+    node: Node,
+    record: &SourceRecord<'_>,
+) -> Result<(), PatchParseError> {
+    let raw = record.raw(write::single_record(&node));
+    push(
+        stack,
+        Element {
+            node,
+            raws: vec![raw],
+        },
+    )
+}
+
+/// Splits the source into records, returning the whitespace after the last one separately.
+fn split_records(source: &str) -> Result<(Vec<SourceRecord<'_>>, &str), PatchParseError> {
+    let mut records = Vec::new();
+    let mut rest = source;
+    let mut line = 1;
+
+    loop {
+        let Some(start) = rest.find(|character: char| !character.is_whitespace()) else {
+            return Ok((records, rest));
+        };
+        let (leading, remaining) = rest.split_at(start);
+        line += leading.matches('\n').count();
+
+        let Some(end) = record_end(remaining) else {
+            return Err(PatchParseError::UnterminatedRecord { line });
+        };
+        let (text, remaining) = remaining.split_at(end);
+        records.push(SourceRecord {
+            leading,
+            text,
+            line,
+        });
+        line += text.matches('\n').count();
+        rest = remaining;
+    }
+}
+
+/// Finds the end of the record, right after the first `;` which is not escaped.
+fn record_end(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, character) in text.char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' => return Some(index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a record to its words, resolving escapes.
+fn lex(text: &str) -> Vec<Lexeme> {
+    let body = text.strip_suffix(';').unwrap_or(text);
+    let mut lexemes = Vec::new();
+    let mut characters = body.chars().peekable();
+
+    loop {
+        while characters
+            .next_if(|character| character.is_whitespace())
+            .is_some()
+        {}
+        let Some(first) = characters.next() else {
+            return lexemes;
+        };
+        if first == ',' {
+            lexemes.push(Lexeme::BareComma);
+            continue;
+        }
+
+        let mut word = String::new();
+        let mut escaped = false;
+        let mut current = Some(first);
+        while let Some(character) = current {
+            if character == '\\' {
+                if let Some(next) = characters.next() {
+                    word.push(next);
+                    escaped = true;
+                }
+            } else {
+                word.push(character);
+            }
+            current =
+                characters.next_if(|character| !character.is_whitespace() && *character != ',');
+        }
+
+        let token = match (escaped, word.as_str()) {
+            (true, ",") => Token::Comma,
+            (true, ";") => Token::Semicolon,
+            (false, _) => float(&word).map_or(Token::Symbol(word), Token::Float),
+            (true, _) => Token::Symbol(word),
+        };
+        lexemes.push(Lexeme::Token(token));
+    }
+}
+
+/// Parses a word as a float the way pd does, words like `inf` or `-` are symbols in pd.
+pub(crate) fn float(word: &str) -> Option<f64> {
+    let first = word.chars().next()?;
+    let numeric = |character: char| {
+        character.is_ascii_digit() || matches!(character, '-' | '+' | '.' | 'e' | 'E')
+    };
+    if !(first.is_ascii_digit() || matches!(first, '-' | '+' | '.'))
+        || !word.chars().all(numeric)
+        || !word.chars().any(|character| character.is_ascii_digit())
+    {
+        return None;
+    }
+    word.parse().ok()
+}
+
+/// Converts lexemes to tokens, a bare comma becomes a comma token.
+fn tokens(lexemes: &[Lexeme]) -> Vec<Token> {
+    lexemes
+        .iter()
+        .map(|lexeme| match lexeme {
+            Lexeme::Token(token) => token.clone(),
+            Lexeme::BareComma => Token::Comma,
+        })
+        .collect()
+}
+
+/// Converts lexemes to tokens, taking the trailing `, f width` of a box out.
+fn tokens_with_width(lexemes: &[Lexeme]) -> (Vec<Token>, Option<i32>) {
+    if let [rest @ .., Lexeme::BareComma, Lexeme::Token(Token::Symbol(f)), Lexeme::Token(width)] =
+        lexemes
+    {
+        if let Some(width) = int(width).filter(|_| f == "f") {
+            return (tokens(rest), Some(width));
+        }
+    }
+    (tokens(lexemes), None)
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "The value is checked to be an integer in the range of i32."
+)]
+fn int(token: &Token) -> Option<i32> {
+    token
+        .as_float()
+        .filter(|value| {
+            value.fract() == 0.0 && (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(value)
+        })
+        .map(|value| value as i32)
+}
+
+fn index(token: &Token) -> Option<usize> {
+    int(token).and_then(|value| usize::try_from(value).ok())
+}
@@ -0,0 +1,204 @@
+use super::{Array, ArrayData, Canvas, CanvasKind, Element, Node, Patch, Raw, Subpatch, Token};
+
+/// Writes the patch, unmodified records are written as they are in the source.
+pub(super) fn write(patch: &Patch) -> String {
+    let mut writer = Writer::default();
+    writer.record(patch.header.as_ref(), canvas_header(&patch.canvas));
+    writer.elements(&patch.canvas.elements);
+    writer.out.push_str(&patch.trailing);
+    writer.out
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+}
+
+impl Writer {
+    /// Writes the original text if the record didn't change since it was parsed, the canonical form otherwise.
+    fn record(&mut self, raw: Option<&Raw>, canonical: String) {
+        match raw {
+            Some(raw) if raw.canonical == canonical => {
+                self.out.push_str(&raw.leading);
+                self.out.push_str(&raw.text);
+            }
+            _ => {
+                if !self.out.is_empty() {
+                    self.out.push('\n');
+                }
+                self.out.push_str(&canonical);
+            }
+        }
+    }
+
+    fn elements(&mut self, elements: &[Element]) {
+        for element in elements {
+            let mut raws = element.raws.iter();
+            match &element.node {
+                Node::Subpatch(subpatch) => {
+                    self.record(raws.next(), canvas_header(&subpatch.canvas));
+                    self.elements(&subpatch.canvas.elements);
+                    self.record(raws.next(), restore(subpatch));
+                }
+                Node::Array(array) => {
+                    self.record(raws.next(), array_header(array));
+                    for data in &array.data {
+                        self.record(raws.next(), array_data(data));
+                    }
+                }
+                node => self.record(raws.next(), single_record(node)),
+            }
+        }
+    }
+}
+
+/// The canonical form of `#N canvas`, the elements of the canvas are not included.
+pub(super) fn canvas_header(canvas: &Canvas) -> String {
+    let mut tokens = vec![
+        Token::from(canvas.x),
+        Token::from(canvas.y),
+        Token::from(canvas.width),
+        Token::from(canvas.height),
+    ];
+    match &canvas.kind {
+        CanvasKind::Root { font_size } => tokens.push(Token::from(*font_size)),
+        CanvasKind::Subpatch { name, open } => {
+            tokens.push(Token::from(name.as_str()));
+            tokens.push(Token::from(i32::from(*open)));
+        }
+    }
+    record("#N", "canvas", &tokens, None)
+}
+
+/// The canonical form of `#X restore` which closes a subpatch.
+pub(super) fn restore(subpatch: &Subpatch) -> String {
+    boxed(
+        "restore",
+        subpatch.x,
+        subpatch.y,
+        &subpatch.args,
+        subpatch.width,
+    )
+}
+
+/// The canonical form of `#X array`, the data of the array is not included.
+pub(super) fn array_header(array: &Array) -> String {
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "Array sizes are far below the precision limit of f64."
+    )]
+    let size = array.size as f64;
+    let tokens = [
+        Token::from(array.name.as_str()),
+        Token::Float(size),
+        Token::from(array.element_type.as_str()),
+        Token::from(array.flags),
+    ];
+    record("#X", "array", &tokens, None)
+}
+
+/// The canonical form of an `#A` record of an array.
+pub(super) fn array_data(data: &ArrayData) -> String {
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "Array indices are far below the precision limit of f64."
+    )]
+    let start = data.start as f64;
+    let tokens: Vec<Token> = core::iter::once(start)
+        .chain(data.values.iter().copied())
+        .map(Token::Float)
+        .collect();
+    let mut out = String::from("#A");
+    for token in &tokens {
+        out.push(' ');
+        write_token(&mut out, token);
+    }
+    out.push(';');
+    out
+}
+
+/// The canonical form of the nodes which are a single record.
+pub(super) fn single_record(node: &Node) -> String {
+    match node {
+        Node::Object(object) => boxed("obj", object.x, object.y, &object.args, object.width),
+        Node::Message(message) => boxed("msg", message.x, message.y, &message.args, message.width),
+        Node::Comment(comment) => boxed("text", comment.x, comment.y, &comment.args, comment.width),
+        Node::AtomBox(atom_box) => boxed(
+            atom_box.kind.selector(),
+            atom_box.x,
+            atom_box.y,
+            &atom_box.args,
+            None,
+        ),
+        Node::Connect(connect) => {
+            let tokens: Vec<Token> = [connect.source, connect.outlet, connect.sink, connect.inlet]
+                .into_iter()
+                .map(index_token)
+                .collect();
+            record("#X", "connect", &tokens, None)
+        }
+        Node::Coords(tokens) => record("#X", "coords", tokens, None),
+        Node::Other(tokens) => {
+            let mut out = String::new();
+            for token in tokens {
+                if !out.is_empty() {
+                    out.push(' ');
+                }
+                write_token(&mut out, token);
+            }
+            out.push(';');
+            out
+        }
+        // These span more than one record and are written by the writer.
+        Node::Subpatch(subpatch) => restore(subpatch),
+        Node::Array(array) => array_header(array),
+    }
+}
+
+fn index_token(index: usize) -> Token {
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "Box indices are far below the precision limit of f64."
+    )]
+    let index = index as f64;
+    Token::Float(index)
+}
+
+/// A record of a box which starts with its position and may end with its width.
+fn boxed(selector: &str, x: i32, y: i32, args: &[Token], width: Option<i32>) -> String {
+    let tokens: Vec<Token> = [Token::from(x), Token::from(y)]
+        .into_iter()
+        .chain(args.iter().cloned())
+        .collect();
+    record("#X", selector, &tokens, width)
+}
+
+fn record(head: &str, selector: &str, tokens: &[Token], width: Option<i32>) -> String {
+    let mut out = format!("{head} {selector}");
+    for token in tokens {
+        out.push(' ');
+        write_token(&mut out, token);
+    }
+    if let Some(width) = width {
+        out.push_str(", f ");
+        out.push_str(&width.to_string());
+    }
+    out.push(';');
+    out
+}
+
+pub(super) fn write_token(out: &mut String, token: &Token) {
+    match token {
+        Token::Float(value) => out.push_str(&value.to_string()),
+        Token::Symbol(symbol) => {
+            for character in symbol.chars() {
+                if matches!(character, '\\' | '$' | ';' | ',') || character.is_whitespace() {
+                    out.push('\\');
+                }
+                out.push(character);
+            }
+        }
+        Token::Comma => out.push_str("\\,"),
+        Token::Semicolon => out.push_str("\\;"),
+    }
+}
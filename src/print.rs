@@ -0,0 +1,213 @@
+/// The importance of a line which pd prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum PrintLevel {
+    /// A critical error, logged with the level `0`.
+    Critical,
+    /// An error, printed with `error:` or logged with the level `1`.
+    Error,
+    /// A warning, printed with `warning:`.
+    Warning,
+    /// A regular post, including the output of `[print]` objects.
+    Post,
+    /// A debug message, logged with the level `3`.
+    Debug,
+    /// A verbose message, logged with the level `4` and above.
+    Verbose,
+}
+
+/// A line which pd prints, classified by its level and where it comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintRecord {
+    /// The number of the instance which printed the line.
+    pub instance: i32,
+    /// The importance of the line.
+    pub level: PrintLevel,
+    /// The prefix of the `[print]` object which printed the line.
+    pub print_object: Option<String>,
+    /// The line without the markers pd prepends for its level and the prefix of the `[print]` object.
+    pub text: String,
+}
+
+impl PrintRecord {
+    /// Classifies a line printed by an instance.
+    ///
+    /// Lines which start with one of the prefixes and `: ` are attributed to `[print]` objects,
+    /// `print` is always a prefix since it is the one of `[print]` without arguments.
+    pub fn parse<T: AsRef<str>>(instance: i32, line: &str, print_prefixes: &[T]) -> Self {
+        let (level, print_object, text) = if let Some(text) = line.strip_prefix("error: ") {
+            (PrintLevel::Error, None, text)
+        } else if let Some(text) = line.strip_prefix("warning: ") {
+            (PrintLevel::Warning, None, text)
+        } else if let Some((level, text)) = log_level(line) {
+            (level, None, text)
+        } else if let Some((prefix, text)) = print_object(line, print_prefixes) {
+            (PrintLevel::Post, Some(prefix.to_owned()), text)
+        } else {
+            (PrintLevel::Post, None, line)
+        };
+        Self {
+            instance,
+            level,
+            print_object,
+            text: text.to_owned(),
+        }
+    }
+
+    /// Forwards the record to the `log` crate with the `libpd_rs::pd` target.
+    ///
+    /// Errors and critical errors are logged as errors, posts as info and verbose messages as trace.
+    #[cfg(feature = "log")]
+    pub fn log(&self) {
+        let level = match self.level {
+            PrintLevel::Critical | PrintLevel::Error => log::Level::Error,
+            PrintLevel::Warning => log::Level::Warn,
+            PrintLevel::Post => log::Level::Info,
+            PrintLevel::Debug => log::Level::Debug,
+            PrintLevel::Verbose => log::Level::Trace,
+        };
+        match &self.print_object {
+            Some(prefix) => log::log!(
+                target: "libpd_rs::pd",
+                level,
+                "[instance {}] [print {prefix}] {}",
+                self.instance,
+                self.text
+            ),
+            None => log::log!(
+                target: "libpd_rs::pd",
+                level,
+                "[instance {}] {}",
+                self.instance,
+                self.text
+            ),
+        }
+    }
+
+    /// Forwards the record to the `tracing` crate as an event with the `libpd_rs::pd` target.
+    ///
+    /// The instance and the prefix of the `[print]` object are recorded as fields of the event.
+    #[cfg(feature = "tracing")]
+    pub fn trace(&self) {
+        let print_object = self.print_object.as_deref().unwrap_or_default();
+        macro_rules! event {
+            ($level:expr) => {
+                tracing::event!(
+                    target: "libpd_rs::pd",
+                    $level,
+                    instance = self.instance,
+                    print_object,
+                    "{}",
+                    self.text
+                )
+            };
+        }
+        match self.level {
+            PrintLevel::Critical | PrintLevel::Error => event!(tracing::Level::ERROR),
+            PrintLevel::Warning => event!(tracing::Level::WARN),
+            PrintLevel::Post => event!(tracing::Level::INFO),
+            PrintLevel::Debug => event!(tracing::Level::DEBUG),
+            PrintLevel::Verbose => event!(tracing::Level::TRACE),
+        }
+    }
+}
+
+/// Reads the level of a line which is logged with `logpost` in the form of `verbose(level): text`.
+fn log_level(line: &str) -> Option<(PrintLevel, &str)> {
+    let (level, text) = line.strip_prefix("verbose(")?.split_once("): ")?;
+    let level = match level.parse::<i32>().ok()? {
+        i32::MIN..=0 => PrintLevel::Critical,
+        1 => PrintLevel::Error,
+        2 => PrintLevel::Post,
+        3 => PrintLevel::Debug,
+        _ => PrintLevel::Verbose,
+    };
+    Some((level, text))
+}
+
+fn print_object<'a, T: AsRef<str>>(line: &'a str, prefixes: &[T]) -> Option<(&'a str, &'a str)> {
+    let (prefix, text) = line.split_once(": ")?;
+    (prefix == "print" || prefixes.iter().any(|known| known.as_ref() == prefix))
+        .then_some((prefix, text))
+}
+
+/// Splits the fragments pd prints into lines.
+///
+/// pd prints a line in many fragments and ends it with a newline.
+#[derive(Debug, Default)]
+pub(crate) struct LineAssembler {
+    line: String,
+}
+
+impl LineAssembler {
+    /// Adds a fragment and calls the function with every line which it completes, empty lines are skipped.
+    pub(crate) fn push<F: FnMut(&str)>(&mut self, fragment: &str, mut line: F) {
+        let mut lines = fragment.split('\n');
+        if let Some(first) = lines.next() {
+            self.line.push_str(first);
+        }
+        for rest in lines {
+            if !self.line.is_empty() {
+                line(&self.line);
+            }
+            self.line.clear();
+            self.line.push_str(rest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::restriction, clippy::nursery, clippy::all, clippy::pedantic)]
+
+    use super::*;
+
+    #[test]
+    fn classifies_lines() {
+        let prefixes = ["synth"];
+        let record = PrintRecord::parse(1, "error: osc~: no method for 'foo'", &prefixes);
+        assert_eq!(record.level, PrintLevel::Error);
+        assert_eq!(record.text, "osc~: no method for 'foo'");
+
+        let record = PrintRecord::parse(1, "warning: DSP loop", &prefixes);
+        assert_eq!(record.level, PrintLevel::Warning);
+
+        let record = PrintRecord::parse(1, "verbose(4): tried /tmp/foo.pd", &prefixes);
+        assert_eq!(record.level, PrintLevel::Verbose);
+        assert_eq!(record.text, "tried /tmp/foo.pd");
+        assert_eq!(
+            PrintRecord::parse(1, "verbose(0): oh no", &prefixes).level,
+            PrintLevel::Critical
+        );
+        assert_eq!(
+            PrintRecord::parse(1, "verbose(3): detail", &prefixes).level,
+            PrintLevel::Debug
+        );
+
+        let record = PrintRecord::parse(2, "synth: 440", &prefixes);
+        assert_eq!(record.instance, 2);
+        assert_eq!(record.level, PrintLevel::Post);
+        assert_eq!(record.print_object.as_deref(), Some("synth"));
+        assert_eq!(record.text, "440");
+
+        let record = PrintRecord::parse(1, "print: bang", &prefixes);
+        assert_eq!(record.print_object.as_deref(), Some("print"));
+
+        let record = PrintRecord::parse(1, "audio I/O stuck: closing audio", &prefixes);
+        assert_eq!(record.level, PrintLevel::Post);
+        assert_eq!(record.print_object, None);
+        assert_eq!(record.text, "audio I/O stuck: closing audio");
+    }
+
+    #[test]
+    fn assembles_lines() {
+        let mut assembler = LineAssembler::default();
+        let mut lines = Vec::new();
+        for fragment in ["synth:", " 440", "\n", "first\nsecond\n\n", "third"] {
+            assembler.push(fragment, |line| lines.push(line.to_owned()));
+        }
+        assert_eq!(lines, ["synth: 440", "first", "second"]);
+        assembler.push("\n", |line| lines.push(line.to_owned()));
+        assert_eq!(lines.last().map(String::as_str), Some("third"));
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    fs::File,
+    io::{BufReader, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use crate::{
+    error::RenderError,
+    functions::{block_size, util::calculate_ticks},
+    Pd,
+};
+
+/// The amount of pd ticks which are processed in one go while rendering.
+const TICKS_PER_BUFFER: usize = 16;
+
+/// The sample format of the rendered WAV file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WavSampleFormat {
+    /// 16-bit signed integer samples.
+    Int16,
+    /// 24-bit signed integer samples.
+    Int24,
+    /// 32-bit float samples, no conversion happens.
+    #[default]
+    Float32,
+}
+
+impl WavSampleFormat {
+    pub(crate) const fn spec(self, channels: u16, sample_rate: u32) -> WavSpec {
+        let (bits_per_sample, sample_format) = match self {
+            Self::Int16 => (16, SampleFormat::Int),
+            Self::Int24 => (24, SampleFormat::Int),
+            Self::Float32 => (32, SampleFormat::Float),
+        };
+        WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            sample_format,
+        }
+    }
+}
+
+/// Renders the output of a [`Pd`] instance to a WAV file faster than realtime, no sound card is involved.
+///
+/// Audio is processed with [`PdAudioContext::process_float`](crate::PdAudioContext::process_float)
+/// and the message queues are drained after each buffer so the handlers registered to the instance keep working.
+///
+/// The channel count and the sample rate are the ones which [`Pd`] is configured with.
+/// DSP needs to be turned on with [`dsp_on`](crate::Pd::dsp_on) before rendering.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{render::{OfflineRenderer, WavSampleFormat}, Pd};
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("tests/patches/sine.pd").unwrap();
+/// pd.dsp_on().unwrap();
+///
+/// OfflineRenderer::new(&pd)
+///     .sample_format(WavSampleFormat::Int24)
+///     .render_seconds("sine.wav", 2.0)
+///     .unwrap();
+/// ```
+pub struct OfflineRenderer<'a> {
+    pd: &'a Pd,
+    sample_format: WavSampleFormat,
+    input: Option<PathBuf>,
+}
+
+impl<'a> OfflineRenderer<'a> {
+    /// Creates a renderer for the instance which writes 32-bit float samples and feeds silence to the inputs.
+    pub const fn new(pd: &'a Pd) -> Self {
+        Self {
+            pd,
+            sample_format: WavSampleFormat::Float32,
+            input: None,
+        }
+    }
+
+    /// Sets the sample format of the rendered file.
+    #[must_use]
+    pub const fn sample_format(mut self, sample_format: WavSampleFormat) -> Self {
+        self.sample_format = sample_format;
+        self
+    }
+
+    /// Feeds the inputs of the instance from a WAV file.
+    ///
+    /// The file needs to match the input channels and the sample rate of the instance,
+    /// inputs are fed with silence after the file ends.
+    #[must_use]
+    pub fn input_wav<T: AsRef<Path>>(mut self, path: T) -> Self {
+        self.input = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Renders the given amount of seconds, rounded up to the next pd tick.
+    ///
+    /// # Errors
+    ///
+    /// See [`render_ticks`](OfflineRenderer::render_ticks).
+    pub fn render_seconds<T: AsRef<Path>>(&self, path: T, seconds: f64) -> Result<(), RenderError> {
+        let block_size = f64::from(block_size());
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "Negative durations saturate to 0 which renders an empty file."
+        )]
+        let ticks = (seconds * f64::from(self.pd.sample_rate()) / block_size).ceil() as usize;
+        self.render_ticks(path, ticks)
+    }
+
+    /// Renders the given amount of pd ticks, each tick is one block of samples for every channel.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`DspInactive`](crate::error::RenderError::DspInactive)
+    /// - [`NoOutputChannels`](crate::error::RenderError::NoOutputChannels)
+    /// - [`InputChannelMismatch`](crate::error::RenderError::InputChannelMismatch)
+    /// - [`SampleRateMismatch`](crate::error::RenderError::SampleRateMismatch)
+    /// - [`Wav`](crate::error::RenderError::Wav)
+    pub fn render_ticks<T: AsRef<Path>>(&self, path: T, ticks: usize) -> Result<(), RenderError> {
+        if !self.pd.audio_active() {
+            return Err(RenderError::DspInactive);
+        }
+
+        let input_channels = self.pd.input_channels();
+        let output_channels = self.pd.output_channels();
+        let channel_count =
+            u16::try_from(output_channels).map_err(|_| RenderError::NoOutputChannels)?;
+        if channel_count == 0 {
+            return Err(RenderError::NoOutputChannels);
+        }
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "Pd is configured with a positive sample rate."
+        )]
+        let sample_rate = self.pd.sample_rate() as u32;
+
+        let mut input = self
+            .input
+            .as_ref()
+            .map(|path| open_input(path, input_channels, sample_rate))
+            .transpose()?;
+
+        let mut writer =
+            WavWriter::create(path, self.sample_format.spec(channel_count, sample_rate))?;
+
+        let block_size = block_size();
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "Block size and channel counts are never negative."
+        )]
+        let (input_block, output_block) = (
+            (block_size * input_channels) as usize,
+            (block_size * output_channels) as usize,
+        );
+        let mut input_buffer = Vec::with_capacity(input_block * TICKS_PER_BUFFER);
+        let mut output_buffer = Vec::with_capacity(output_block * TICKS_PER_BUFFER);
+
+        let ctx = self.pd.audio_context();
+        let mut remaining = ticks;
+        while remaining > 0 {
+            let now = remaining.min(TICKS_PER_BUFFER);
+            input_buffer.clear();
+            input_buffer.resize(input_block * now, 0.0_f32);
+            output_buffer.clear();
+            output_buffer.resize(output_block * now, 0.0_f32);
+
+            if let Some(samples) = input.as_mut() {
+                for (slot, sample) in input_buffer.iter_mut().zip(samples) {
+                    *slot = sample?;
+                }
+            }
+
+            ctx.receive_messages_from_pd();
+            ctx.receive_midi_messages_from_pd();
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_possible_wrap,
+                reason = "The buffer holds at most TICKS_PER_BUFFER blocks."
+            )]
+            let ticks = calculate_ticks(output_channels, output_buffer.len() as i32);
+            ctx.process_float(ticks, &input_buffer, &mut output_buffer);
+
+            write_samples(&mut writer, self.sample_format, &output_buffer)?;
+            remaining -= now;
+        }
+
+        ctx.receive_messages_from_pd();
+        ctx.receive_midi_messages_from_pd();
+        writer.finalize()?;
+        Ok(())
+    }
+}
+
+/// An iterator over the samples of a WAV file converted to `f32`.
+type InputSamples = Box<dyn Iterator<Item = Result<f32, hound::Error>>>;
+
+fn open_input(path: &Path, channels: i32, sample_rate: u32) -> Result<InputSamples, RenderError> {
+    let reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    if i32::from(spec.channels) != channels {
+        return Err(RenderError::InputChannelMismatch {
+            expected: channels,
+            found: spec.channels,
+        });
+    }
+    if spec.sample_rate != sample_rate {
+        return Err(RenderError::SampleRateMismatch {
+            expected: sample_rate,
+            found: spec.sample_rate,
+        });
+    }
+
+    Ok(read_samples(reader))
+}
+
+/// Reads every sample of a WAV file as `f32` in the range of `-1.0..=1.0`.
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "Integer samples are at most 32 bits, the precision of f32 is enough for audio."
+)]
+pub(crate) fn read_samples(reader: WavReader<BufReader<File>>) -> InputSamples {
+    let spec = reader.spec();
+    match spec.sample_format {
+        SampleFormat::Float => Box::new(reader.into_samples::<f32>()),
+        SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            Box::new(
+                reader
+                    .into_samples::<i32>()
+                    .map(move |sample| sample.map(|sample| sample as f32 / scale)),
+            )
+        }
+    }
+}
+
+/// Writes `f32` samples in the range of `-1.0..=1.0` in the given format, integer samples are clipped.
+pub(crate) fn write_samples<W: Write + Seek>(
+    writer: &mut WavWriter<W>,
+    sample_format: WavSampleFormat,
+    samples: &[f32],
+) -> Result<(), hound::Error> {
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Samples are clamped to the range of the integer type before the conversion."
+    )]
+    for sample in samples {
+        match sample_format {
+            WavSampleFormat::Int16 => {
+                writer.write_sample((sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16)?;
+            }
+            WavSampleFormat::Int24 => {
+                writer.write_sample((sample.clamp(-1.0, 1.0) * 8_388_607.0) as i32)?;
+            }
+            WavSampleFormat::Float32 => writer.write_sample(*sample)?,
+        }
+    }
+    Ok(())
+}
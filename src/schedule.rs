@@ -0,0 +1,337 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    time::SystemTime,
+};
+
+use crate::{
+    error::{PdError, ProcessError, ScheduleError},
+    functions::{block_size, send},
+    types::AddressedMessage,
+    Atom, PdAudioContext,
+};
+
+/// How many messages a [`Scheduler`] keeps pending, the space for them is allocated when it is created.
+const PENDING_CAPACITY: usize = 1024;
+
+/// The point in time a scheduled message is sent to pd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timestamp {
+    /// A position on the sample clock of the [`Scheduler`], which starts from `0`
+    /// and advances with every frame it processes.
+    Samples(u64),
+    /// A point in wall clock time, converted to the sample clock when the next buffer is processed.
+    Time(SystemTime),
+}
+
+/// A message which is sent to pd when its time comes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ScheduledMessage {
+    /// A bang sent to a receiver.
+    Bang {
+        /// The name of the receiver.
+        receiver: String,
+    },
+    /// A float sent to a receiver.
+    Float {
+        /// The name of the receiver.
+        receiver: String,
+        /// The value.
+        value: f64,
+    },
+    /// A symbol sent to a receiver.
+    Symbol {
+        /// The name of the receiver.
+        receiver: String,
+        /// The symbol.
+        symbol: String,
+    },
+    /// A list sent to a receiver.
+    List {
+        /// The name of the receiver.
+        receiver: String,
+        /// The elements of the list.
+        list: Vec<Atom>,
+    },
+    /// A typed message sent to a receiver.
+    Message {
+        /// The name of the receiver.
+        receiver: String,
+        /// The selector of the message.
+        selector: String,
+        /// The arguments of the message.
+        list: Vec<Atom>,
+    },
+}
+
+impl ScheduledMessage {
+    pub(crate) fn send(&self) -> Result<(), PdError> {
+        match self {
+            Self::Bang { receiver } => send::send_bang_to(receiver)?,
+            Self::Float { receiver, value } => send::send_double_to(receiver, *value)?,
+            Self::Symbol { receiver, symbol } => send::send_symbol_to(receiver, symbol)?,
+            Self::List { receiver, list } => send::send_list_to(receiver, list)?,
+            Self::Message {
+                receiver,
+                selector,
+                list,
+            } => send::send_message_to(receiver.as_str(), selector.as_str(), list)?,
+        }
+        Ok(())
+    }
+}
+
+impl From<AddressedMessage> for ScheduledMessage {
+    fn from(message: AddressedMessage) -> Self {
+        Self::Message {
+            receiver: message.receiver,
+            selector: message.selector,
+            list: message.atoms,
+        }
+    }
+}
+
+/// Sends timestamped messages to a [`Scheduler`] from any thread.
+///
+/// Created with [`Pd::scheduler`](crate::Pd::scheduler), it could be cloned freely.
+#[derive(Debug, Clone)]
+pub struct ScheduleSender {
+    sender: mpsc::Sender<(Timestamp, ScheduledMessage)>,
+    position: Arc<AtomicU64>,
+    sample_rate: u32,
+}
+
+impl ScheduleSender {
+    /// Schedules a message to be sent to pd at the given time.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`SchedulerDropped`](crate::error::ScheduleError::SchedulerDropped)
+    pub fn send(&self, at: Timestamp, message: ScheduledMessage) -> Result<(), ScheduleError> {
+        self.sender
+            .send((at, message))
+            .map_err(|_| ScheduleError::SchedulerDropped)
+    }
+
+    /// Schedules a message to be sent after the given amount of samples from the current position of the sample clock.
+    ///
+    /// # Errors
+    ///
+    /// See [`send`](ScheduleSender::send).
+    pub fn send_in(&self, samples: u64, message: ScheduledMessage) -> Result<(), ScheduleError> {
+        self.send(
+            Timestamp::Samples(self.position().saturating_add(samples)),
+            message,
+        )
+    }
+
+    /// Returns the position of the sample clock at the start of the buffer which is processed last.
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Acquire)
+    }
+
+    /// Returns the sample rate of the sample clock.
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+/// Processes audio in single pd ticks and sends each scheduled message right before the tick it belongs to.
+///
+/// It is used in the audio thread in place of [`PdAudioContext::process_float`].
+///
+/// # Accuracy
+///
+/// pd handles messages between its ticks, which are [`block_size`] (64) frames long.
+/// A message is sent right before the tick which contains its position on the sample clock is processed,
+/// so it takes effect at the start of that tick, at most `block_size - 1` frames earlier than its position.
+/// This holds for every message which arrives at the scheduler before the buffer containing its position is processed,
+/// messages which arrive late are sent before the first tick of the next buffer.
+///
+/// [`Timestamp::Time`] is converted to the sample clock when a buffer starts to be processed,
+/// so its accuracy additionally depends on the timing jitter of the audio callback.
+///
+/// Messages with the same position are sent in the order they are scheduled.
+///
+/// At most 1024 messages are kept pending in the scheduler so processing doesn't allocate,
+/// the ones which are scheduled beyond that wait in the channel until earlier ones are sent.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{
+///     schedule::{ScheduledMessage, Timestamp},
+///     Pd,
+/// };
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// pd.open_patch("sequencer.pd").unwrap();
+/// pd.dsp_on().unwrap();
+///
+/// let (sender, mut scheduler) = pd.scheduler();
+///
+/// // In the sequencer thread
+/// sender
+///     .send(
+///         Timestamp::Samples(44100),
+///         ScheduledMessage::Float {
+///             receiver: "note".to_owned(),
+///             value: 60.0,
+///         },
+///     )
+///     .unwrap();
+///
+/// // In the audio callback
+/// let mut output = [0.0_f32; 1024];
+/// scheduler.process_float(&[], &mut output).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Scheduler {
+    context: PdAudioContext,
+    receiver: mpsc::Receiver<(Timestamp, ScheduledMessage)>,
+    /// Pending messages with their position and the order they arrived in, the next one to send is the last.
+    pending: Vec<(u64, u64, ScheduledMessage)>,
+    received: u64,
+    position: Arc<AtomicU64>,
+    sample_rate: u32,
+    input_channels: usize,
+    output_channels: usize,
+}
+
+impl Scheduler {
+    pub(crate) fn new(
+        context: PdAudioContext,
+        sample_rate: u32,
+        input_channels: usize,
+        output_channels: usize,
+    ) -> (ScheduleSender, Self) {
+        let (sender, receiver) = mpsc::channel();
+        let position = Arc::new(AtomicU64::new(0));
+        (
+            ScheduleSender {
+                sender,
+                position: Arc::clone(&position),
+                sample_rate,
+            },
+            Self {
+                context,
+                receiver,
+                pending: Vec::with_capacity(PENDING_CAPACITY),
+                received: 0,
+                position,
+                sample_rate,
+                input_channels,
+                output_channels,
+            },
+        )
+    }
+
+    /// Returns the position of the sample clock, which is the first frame of the next buffer.
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Acquire)
+    }
+
+    /// Processes interleaved buffers tick by tick, sending scheduled messages in between.
+    ///
+    /// The output buffer needs to hold a whole number of ticks for the output channels of the instance
+    /// and the input buffer the same amount of frames for the input channels,
+    /// the buffers are checked before anything is processed.
+    ///
+    /// Every message is tried to be sent even if some of them fail.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`ProcessError`]
+    ///   - [`OutputLengthMismatch`](crate::error::ProcessError::OutputLengthMismatch)
+    ///   - [`InputLengthMismatch`](crate::error::ProcessError::InputLengthMismatch)
+    ///
+    /// Otherwise the first error which occurs while sending a message, which is one of the errors of the `send_*_to` functions in
+    /// [`functions::send`](crate::functions::send).
+    pub fn process_float(&mut self, input: &[f32], output: &mut [f32]) -> Result<(), PdError> {
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "The block size of pd is a positive constant."
+        )]
+        let block_size = block_size() as usize;
+        let input_block = block_size * self.input_channels;
+        let output_block = block_size * self.output_channels;
+        if output_block == 0 {
+            return Ok(());
+        }
+        let ticks = output.len() / output_block;
+        if output.len() != ticks * output_block {
+            return Err(ProcessError::OutputLengthMismatch {
+                expected: ticks * output_block,
+                found: output.len(),
+            }
+            .into());
+        }
+        if input.len() != ticks * input_block {
+            return Err(ProcessError::InputLengthMismatch {
+                expected: ticks * input_block,
+                found: input.len(),
+            }
+            .into());
+        }
+
+        self.receive();
+        // Messages are sent to the current instance, which could be another one processed on this thread.
+        self.context.instance.set_as_current();
+
+        let mut result = Ok(());
+        for (tick, output) in output.chunks_exact_mut(output_block).enumerate() {
+            let position = self.position();
+            let tick_end = position.saturating_add(block_size as u64);
+            while let Some((.., message)) = self.pending.pop_if(|(at, ..)| *at < tick_end) {
+                if let Err(err) = message.send() {
+                    result = result.and(Err(err));
+                }
+            }
+
+            let input = input
+                .get(tick * input_block..(tick + 1) * input_block)
+                .unwrap_or_default();
+            self.context.try_process_float(1, input, output)?;
+            self.position.store(tick_end, Ordering::Release);
+        }
+        result
+    }
+
+    /// Moves the messages which arrived to the pending ones, converting wall clock times to the sample clock.
+    ///
+    /// Messages are only taken from the channel while there is room for them, so the pending ones never reallocate.
+    fn receive(&mut self) {
+        let now = SystemTime::now();
+        let position = self.position();
+        while self.pending.len() < self.pending.capacity() {
+            let Ok((at, message)) = self.receiver.try_recv() else {
+                break;
+            };
+            let at = match at {
+                Timestamp::Samples(at) => at,
+                Timestamp::Time(time) => {
+                    let ahead = time.duration_since(now).unwrap_or_default();
+                    #[expect(
+                        clippy::cast_possible_truncation,
+                        clippy::cast_sign_loss,
+                        reason = "The amount of frames is positive and far below the limits of u64."
+                    )]
+                    let frames = (ahead.as_secs_f64() * f64::from(self.sample_rate)) as u64;
+                    position.saturating_add(frames)
+                }
+            };
+            // Sorted from the latest to the earliest, so the next message is popped from the end.
+            let key = (at, self.received);
+            let index = self
+                .pending
+                .partition_point(|(at, received, _)| (*at, *received) > key);
+            self.pending.insert(index, (at, self.received, message));
+            self.received += 1;
+        }
+    }
+}
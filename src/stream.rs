@@ -0,0 +1,89 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use tokio::sync::mpsc;
+
+use crate::{error::RequestError, types::AddressedMessage};
+
+/// An async stream of the messages which are sent to a source in pd, created with [`Pd::stream_from`](crate::Pd::stream_from).
+///
+/// Messages are delivered while the queues of the instance are drained with
+/// [`receive_messages_from_pd`](crate::PdAudioContext::receive_messages_from_pd), usually in the audio callback.
+/// The stream ends when the [`Pd`](crate::Pd) instance is dropped.
+///
+/// It implements [`Stream`](futures_core::Stream), so the combinators of the async ecosystem work with it.
+#[derive(Debug)]
+pub struct PdStream {
+    source: String,
+    receiver: mpsc::UnboundedReceiver<AddressedMessage>,
+}
+
+impl PdStream {
+    pub(crate) const fn new(
+        source: String,
+        receiver: mpsc::UnboundedReceiver<AddressedMessage>,
+    ) -> Self {
+        Self { source, receiver }
+    }
+
+    /// Returns the name of the source the stream receives from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Waits for the next message, `None` if the instance is dropped.
+    pub async fn recv(&mut self) -> Option<AddressedMessage> {
+        self.receiver.recv().await
+    }
+}
+
+impl futures_core::Stream for PdStream {
+    type Item = AddressedMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// A future which resolves to the reply of a request, created with [`Pd::request`](crate::Pd::request).
+///
+/// It doesn't borrow the instance, so it could be spawned on a tokio runtime.
+pub struct Request {
+    inner: Pin<Box<dyn Future<Output = Result<AddressedMessage, RequestError>> + Send>>,
+}
+
+impl Request {
+    /// Waits for the first message of the stream for at most the timeout.
+    pub(crate) fn new(stream: Result<PdStream, RequestError>, timeout: Duration) -> Self {
+        Self {
+            inner: Box::pin(async move {
+                let mut stream = stream?;
+                tokio::time::timeout(timeout, stream.recv())
+                    .await
+                    .map_err(|_| RequestError::Timeout {
+                        reply_source: stream.source.clone(),
+                        timeout,
+                    })?
+                    .ok_or(RequestError::Closed)
+            }),
+        }
+    }
+}
+
+impl core::fmt::Debug for Request {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Request").finish_non_exhaustive()
+    }
+}
+
+impl Future for Request {
+    type Output = Result<AddressedMessage, RequestError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.as_mut().poll(cx)
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    env, fs, mem,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use hound::{WavReader, WavWriter};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    error::TestingError,
+    functions::block_size,
+    render::{read_samples, write_samples, WavSampleFormat},
+    schedule::ScheduledMessage,
+    types::PdMessage,
+    Atom, Pd,
+};
+
+/// When this environment variable is set to a value other than empty, `0`, `false`, `no` or `off`,
+/// golden files are written with the output instead of being compared.
+pub const UPDATE_GOLDEN_ENV: &str = "LIBPD_RS_UPDATE_GOLDEN";
+
+/// Runs a patch in its own [`Pd`] instance tick by tick and captures its output audio and messages.
+///
+/// Messages are sent right before the tick they are scheduled for and the inputs are fed from
+/// an interleaved buffer, so every run of the same patch produces the same output.
+/// The output is compared against golden files with [`TestOutput`].
+///
+/// Set [`UPDATE_GOLDEN_ENV`] to write the golden files when a patch changes on purpose.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{schedule::ScheduledMessage, testing::PatchTest};
+///
+/// let mut test = PatchTest::new("tests/patches/echo.pd", 0, 2, 44100).unwrap();
+/// test.pd_mut().subscribe_to("float_from_pd").unwrap();
+/// test.send_at(
+///     4,
+///     ScheduledMessage::Float {
+///         receiver: "float_from_rust".to_owned(),
+///         value: 42.0,
+///     },
+/// );
+///
+/// let output = test.run(100).unwrap();
+/// output.compare_messages("tests/golden/echo.json", 0.0).unwrap();
+/// output.compare_audio("tests/golden/echo.wav", 1e-6).unwrap();
+/// ```
+pub struct PatchTest {
+    pd: Pd,
+    messages: mpsc::Receiver<PdMessage>,
+    pending: Vec<(usize, ScheduledMessage)>,
+    input: Vec<f32>,
+    tick: usize,
+}
+
+impl PatchTest {
+    /// Creates an instance with the channels and the sample rate, opens the patch in it and turns DSP on.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`Pd`](crate::error::TestingError::Pd)
+    pub fn new<T: AsRef<Path>>(
+        patch: T,
+        input_channels: i32,
+        output_channels: i32,
+        sample_rate: i32,
+    ) -> Result<Self, TestingError> {
+        let mut pd = Pd::init_and_configure(input_channels, output_channels, sample_rate)?;
+        let messages = pd.message_receiver();
+        pd.open_patch(patch)?;
+        pd.dsp_on()?;
+        Ok(Self {
+            pd,
+            messages,
+            pending: Vec::new(),
+            input: Vec::new(),
+            tick: 0,
+        })
+    }
+
+    /// Returns the instance the patch runs in.
+    pub const fn pd(&self) -> &Pd {
+        &self.pd
+    }
+
+    /// Returns the instance the patch runs in, to subscribe to the receivers whose messages are captured.
+    pub fn pd_mut(&mut self) -> &mut Pd {
+        &mut self.pd
+    }
+
+    /// Schedules a message to be sent right before the tick is processed.
+    ///
+    /// Messages for the same tick are sent in the order they are scheduled,
+    /// messages for ticks which are already processed are sent before the next one.
+    pub fn send_at(&mut self, tick: usize, message: ScheduledMessage) -> &mut Self {
+        self.pending.push((tick, message));
+        self
+    }
+
+    /// Feeds the inputs from the interleaved samples, the inputs are silent after the samples end.
+    pub fn input(&mut self, samples: Vec<f32>) -> &mut Self {
+        self.input = samples;
+        self
+    }
+
+    /// Processes the ticks and returns the audio and the messages they output.
+    ///
+    /// Consecutive runs continue from the tick the last one stopped at.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`Pd`](crate::error::TestingError::Pd)
+    pub fn run(&mut self, ticks: usize) -> Result<TestOutput, TestingError> {
+        #[expect(
+            clippy::cast_sign_loss,
+            reason = "Block size and channel counts are never negative."
+        )]
+        let (block, input_channels, output_channels) = (
+            block_size() as usize,
+            self.pd.input_channels() as usize,
+            self.pd.output_channels() as usize,
+        );
+        let mut input = vec![0.0_f32; block * input_channels];
+        let mut output = vec![0.0_f32; block * output_channels];
+        let mut audio = Vec::with_capacity(ticks * output.len());
+        let mut messages = Vec::new();
+
+        let ctx = self.pd.audio_context();
+        for _ in 0..ticks {
+            let (due, pending): (Vec<_>, Vec<_>) = mem::take(&mut self.pending)
+                .into_iter()
+                .partition(|(tick, _)| *tick <= self.tick);
+            self.pending = pending;
+            {
+                let _guard = self.pd.set_as_active_instance();
+                for (_, message) in due {
+                    message.send()?;
+                }
+            }
+
+            input.fill(0.0);
+            let fed = self.input.iter().skip(self.tick * input.len());
+            for (slot, sample) in input.iter_mut().zip(fed) {
+                *slot = *sample;
+            }
+            ctx.process_float(1, &input, &mut output);
+            ctx.receive_messages_from_pd();
+            ctx.receive_midi_messages_from_pd();
+
+            audio.extend_from_slice(&output);
+            messages.extend(self.messages.try_iter().map(|message| CapturedMessage {
+                tick: self.tick,
+                message,
+            }));
+            self.tick += 1;
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            reason = "Channel counts are far below u16::MAX and the sample rate is positive."
+        )]
+        let (channels, sample_rate) = (output_channels as u16, self.pd.sample_rate() as u32);
+        Ok(TestOutput {
+            channels,
+            sample_rate,
+            audio,
+            messages,
+        })
+    }
+}
+
+/// A message which is output by a patch during a [`PatchTest`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedMessage {
+    /// The tick which the message is output while processing.
+    pub tick: usize,
+    /// The message.
+    pub message: PdMessage,
+}
+
+/// The audio and the messages a [`PatchTest`] run outputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestOutput {
+    /// The amount of output channels.
+    pub channels: u16,
+    /// The sample rate of the instance.
+    pub sample_rate: u32,
+    /// The interleaved output samples.
+    pub audio: Vec<f32>,
+    /// The messages in the order they are output.
+    pub messages: Vec<CapturedMessage>,
+}
+
+impl TestOutput {
+    /// Compares the audio with a golden WAV file, samples may differ as much as the tolerance.
+    ///
+    /// The golden file is written instead if [`UPDATE_GOLDEN_ENV`] is set.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`GoldenMissing`](crate::error::TestingError::GoldenMissing)
+    /// - [`SpecMismatch`](crate::error::TestingError::SpecMismatch)
+    /// - [`LengthMismatch`](crate::error::TestingError::LengthMismatch)
+    /// - [`AudioMismatch`](crate::error::TestingError::AudioMismatch)
+    /// - [`Wav`](crate::error::TestingError::Wav)
+    pub fn compare_audio<T: AsRef<Path>>(
+        &self,
+        golden: T,
+        tolerance: f32,
+    ) -> Result<(), TestingError> {
+        let golden = golden.as_ref();
+        if update_golden() {
+            return self.save_audio(golden);
+        }
+        if !golden.exists() {
+            return Err(TestingError::GoldenMissing(golden.to_path_buf()));
+        }
+        let reader = WavReader::open(golden)?;
+        let spec = reader.spec();
+        if (spec.channels, spec.sample_rate) != (self.channels, self.sample_rate) {
+            return Err(TestingError::SpecMismatch {
+                expected: (spec.channels, spec.sample_rate),
+                found: (self.channels, self.sample_rate),
+            });
+        }
+        let expected = read_samples(reader).collect::<Result<Vec<f32>, _>>()?;
+        if expected.len() != self.audio.len() {
+            return Err(TestingError::LengthMismatch {
+                expected: expected.len(),
+                found: self.audio.len(),
+            });
+        }
+
+        let channels = usize::from(self.channels).max(1);
+        for (index, (expected, found)) in expected.iter().zip(&self.audio).enumerate() {
+            // A NaN on either side is always a mismatch.
+            let difference = (expected - found).abs();
+            if difference.is_nan() || difference > tolerance {
+                return Err(TestingError::AudioMismatch {
+                    frame: index.checked_div(channels).unwrap_or(0),
+                    channel: index % channels,
+                    expected: *expected,
+                    found: *found,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares the messages with a golden JSON file, numbers may differ as much as the tolerance.
+    ///
+    /// The golden file is written instead if [`UPDATE_GOLDEN_ENV`] is set.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`GoldenMissing`](crate::error::TestingError::GoldenMissing)
+    /// - [`MessageMismatch`](crate::error::TestingError::MessageMismatch)
+    /// - [`Json`](crate::error::TestingError::Json)
+    /// - [`Io`](crate::error::TestingError::Io)
+    pub fn compare_messages<T: AsRef<Path>>(
+        &self,
+        golden: T,
+        tolerance: f64,
+    ) -> Result<(), TestingError> {
+        let golden = golden.as_ref();
+        if update_golden() {
+            return self.save_messages(golden);
+        }
+        if !golden.exists() {
+            return Err(TestingError::GoldenMissing(golden.to_path_buf()));
+        }
+        let expected: Vec<Value> = serde_json::from_str(&fs::read_to_string(golden)?)?;
+        let found: Vec<Value> = self.messages.iter().map(message_json).collect();
+
+        let none = Value::Null;
+        for index in 0..expected.len().max(found.len()) {
+            let (expected, found) = (
+                expected.get(index).unwrap_or(&none),
+                found.get(index).unwrap_or(&none),
+            );
+            if !values_match(expected, found, tolerance) {
+                return Err(TestingError::MessageMismatch {
+                    index,
+                    expected: expected.to_string(),
+                    found: found.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the audio to a WAV file of 32-bit float samples.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`Wav`](crate::error::TestingError::Wav)
+    /// - [`Io`](crate::error::TestingError::Io)
+    pub fn save_audio<T: AsRef<Path>>(&self, path: T) -> Result<(), TestingError> {
+        create_parent(path.as_ref())?;
+        let sample_format = WavSampleFormat::Float32;
+        let mut writer =
+            WavWriter::create(path, sample_format.spec(self.channels, self.sample_rate))?;
+        write_samples(&mut writer, sample_format, &self.audio)?;
+        writer.finalize()?;
+        Ok(())
+    }
+
+    /// Writes the messages to a JSON file, one object for each message.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`Json`](crate::error::TestingError::Json)
+    /// - [`Io`](crate::error::TestingError::Io)
+    pub fn save_messages<T: AsRef<Path>>(&self, path: T) -> Result<(), TestingError> {
+        create_parent(path.as_ref())?;
+        let messages: Vec<Value> = self.messages.iter().map(message_json).collect();
+        let mut json = serde_json::to_string_pretty(&messages)?;
+        json.push('\n');
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+fn update_golden() -> bool {
+    env::var(UPDATE_GOLDEN_ENV).is_ok_and(|value| {
+        !matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "" | "0" | "false" | "no" | "off"
+        )
+    })
+}
+
+fn create_parent(path: &Path) -> Result<(), TestingError> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// Compares JSON values structurally, numbers are compared with the tolerance.
+fn values_match(expected: &Value, found: &Value, tolerance: f64) -> bool {
+    match (expected, found) {
+        (Value::Number(expected), Value::Number(found)) => {
+            match (expected.as_f64(), found.as_f64()) {
+                (Some(expected), Some(found)) => (expected - found).abs() <= tolerance,
+                _ => expected == found,
+            }
+        }
+        (Value::Array(expected), Value::Array(found)) => {
+            expected.len() == found.len()
+                && expected
+                    .iter()
+                    .zip(found)
+                    .all(|(expected, found)| values_match(expected, found, tolerance))
+        }
+        (Value::Object(expected), Value::Object(found)) => {
+            expected.len() == found.len()
+                && expected.iter().all(|(key, expected)| {
+                    found
+                        .get(key)
+                        .is_some_and(|found| values_match(expected, found, tolerance))
+                })
+        }
+        (expected, found) => expected == found,
+    }
+}
+
+fn atoms_json(atoms: &[Atom]) -> Value {
+    atoms
+        .iter()
+        .map(|atom| match atom {
+            Atom::Float(value) => json!(value),
+            Atom::Symbol(symbol) => json!(symbol),
+        })
+        .collect()
+}
+
+/// The JSON form of a captured message, an object with its tick, its kind and its fields.
+fn message_json(captured: &CapturedMessage) -> Value {
+    let (kind, fields) = match &captured.message {
+        PdMessage::Print(text) => ("print", json!({ "text": text })),
+        PdMessage::Bang { source } => ("bang", json!({ "source": source })),
+        PdMessage::Float { source, value } => {
+            ("float", json!({ "source": source, "value": value }))
+        }
+        PdMessage::Double { source, value } => {
+            ("float", json!({ "source": source, "value": value }))
+        }
+        PdMessage::Symbol { source, symbol } => {
+            ("symbol", json!({ "source": source, "symbol": symbol }))
+        }
+        PdMessage::List { source, list } => (
+            "list",
+            json!({ "source": source, "list": atoms_json(list) }),
+        ),
+        PdMessage::Message {
+            source,
+            selector,
+            list,
+        } => (
+            "message",
+            json!({ "source": source, "selector": selector, "list": atoms_json(list) }),
+        ),
+        PdMessage::MidiNoteOn {
+            channel,
+            pitch,
+            velocity,
+        } => (
+            "note_on",
+            json!({ "channel": channel, "pitch": pitch, "velocity": velocity }),
+        ),
+        PdMessage::MidiControlChange {
+            channel,
+            controller,
+            value,
+        } => (
+            "control_change",
+            json!({ "channel": channel, "controller": controller, "value": value }),
+        ),
+        PdMessage::MidiProgramChange { channel, value } => (
+            "program_change",
+            json!({ "channel": channel, "value": value }),
+        ),
+        PdMessage::MidiPitchBend { channel, value } => {
+            ("pitch_bend", json!({ "channel": channel, "value": value }))
+        }
+        PdMessage::MidiAfterTouch { channel, value } => {
+            ("after_touch", json!({ "channel": channel, "value": value }))
+        }
+        PdMessage::MidiPolyAfterTouch {
+            channel,
+            pitch,
+            value,
+        } => (
+            "poly_after_touch",
+            json!({ "channel": channel, "pitch": pitch, "value": value }),
+        ),
+        PdMessage::MidiByte { port, byte } => ("midi_byte", json!({ "port": port, "byte": byte })),
+    };
+
+    let mut object = Map::new();
+    object.insert("tick".to_owned(), json!(captured.tick));
+    object.insert("kind".to_owned(), json!(kind));
+    if let Value::Object(fields) = fields {
+        object.extend(fields);
+    }
+    Value::Object(object)
+}
+
+/// The path of a golden file in the `golden` directory next to the tests of the crate which is tested.
+///
+/// The crate is found through `CARGO_MANIFEST_DIR`, which cargo sets while it runs tests.
+/// When the test binary runs without cargo and it is not set,
+/// the path is `tests/golden/<name>` relative to the current working directory.
+pub fn golden_path<T: AsRef<Path>>(name: T) -> PathBuf {
+    env::var_os("CARGO_MANIFEST_DIR")
+        .map_or_else(PathBuf::new, PathBuf::from)
+        .join("tests")
+        .join("golden")
+        .join(name)
+}
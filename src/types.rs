@@ -1,4 +1,7 @@
 use core::ffi;
+use std::path::PathBuf;
+
+use crate::Atom;
 
 /// The handle which is returned from opening a patch.
 ///
@@ -42,3 +45,252 @@ impl From<*mut ffi::c_void> for ReceiverHandle {
         Self(ptr)
     }
 }
+
+/// A message received from pd, as delivered by [`Pd::message_receiver`](crate::Pd::message_receiver).
+///
+/// Messages are sent to the channel while the queues are drained with
+/// [`receive_messages_from_pd`](crate::functions::receive::receive_messages_from_pd) and
+/// [`receive_midi_messages_from_pd`](crate::functions::receive::receive_midi_messages_from_pd).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum PdMessage {
+    /// A line printed by pd.
+    Print(String),
+    /// A bang sent to a subscribed receiver.
+    Bang {
+        /// The name of the receiver.
+        source: String,
+    },
+    /// A float sent to a subscribed receiver, only emitted when [`Pd::on_float`](crate::Pd::on_float) is registered.
+    Float {
+        /// The name of the receiver.
+        source: String,
+        /// The value which is sent.
+        value: f32,
+    },
+    /// A float sent to a subscribed receiver.
+    Double {
+        /// The name of the receiver.
+        source: String,
+        /// The value which is sent.
+        value: f64,
+    },
+    /// A symbol sent to a subscribed receiver.
+    Symbol {
+        /// The name of the receiver.
+        source: String,
+        /// The symbol which is sent.
+        symbol: String,
+    },
+    /// A list sent to a subscribed receiver.
+    List {
+        /// The name of the receiver.
+        source: String,
+        /// The elements of the list.
+        list: Vec<Atom>,
+    },
+    /// A typed message sent to a subscribed receiver.
+    Message {
+        /// The name of the receiver.
+        source: String,
+        /// The selector of the message.
+        selector: String,
+        /// The arguments of the message.
+        list: Vec<Atom>,
+    },
+    /// A midi note on, sent from `[noteout]`.
+    MidiNoteOn {
+        /// The midi channel, zero indexed.
+        channel: i32,
+        /// The pitch of the note.
+        pitch: i32,
+        /// The velocity of the note.
+        velocity: i32,
+    },
+    /// A midi control change, sent from `[ctlout]`.
+    MidiControlChange {
+        /// The midi channel, zero indexed.
+        channel: i32,
+        /// The controller number.
+        controller: i32,
+        /// The value of the controller.
+        value: i32,
+    },
+    /// A midi program change, sent from `[pgmout]`.
+    MidiProgramChange {
+        /// The midi channel, zero indexed.
+        channel: i32,
+        /// The program number.
+        value: i32,
+    },
+    /// A midi pitch bend, sent from `[bendout]`.
+    MidiPitchBend {
+        /// The midi channel, zero indexed.
+        channel: i32,
+        /// The amount of bend in the range of `-8192..=8191`.
+        value: i32,
+    },
+    /// A midi after touch, sent from `[touchout]`.
+    MidiAfterTouch {
+        /// The midi channel, zero indexed.
+        channel: i32,
+        /// The amount of pressure.
+        value: i32,
+    },
+    /// A midi poly after touch, sent from `[polytouchout]`.
+    MidiPolyAfterTouch {
+        /// The midi channel, zero indexed.
+        channel: i32,
+        /// The pitch of the note.
+        pitch: i32,
+        /// The amount of pressure.
+        value: i32,
+    },
+    /// A raw midi byte, sent from `[midiout]`.
+    MidiByte {
+        /// The midi port.
+        port: i32,
+        /// The byte which is sent.
+        byte: i32,
+    },
+}
+
+/// A message addressed to a receiver, with its selector and its arguments.
+///
+/// Every message which is sent to or received from a receiver in pd fits in this shape,
+/// a float is a message with the selector `float` and a single float argument for example.
+/// With the `serde` feature it could be serialized to persist or transmit message traffic, e.g. for presets or logs.
+///
+/// # Example
+/// ```no_run
+/// use libpd_rs::{types::AddressedMessage, Pd};
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let message = AddressedMessage::new("synth", "note", vec![60.into(), 100.into()]);
+/// pd.send_message_to(&message.receiver, &message.selector, &message.atoms)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddressedMessage {
+    /// The name of the receiver.
+    pub receiver: String,
+    /// The selector of the message, `bang`, `float`, `symbol` and `list` for the basic types.
+    pub selector: String,
+    /// The arguments of the message.
+    pub atoms: Vec<Atom>,
+}
+
+impl AddressedMessage {
+    /// Creates a message for a receiver.
+    pub fn new<R: Into<String>, S: Into<String>>(
+        receiver: R,
+        selector: S,
+        atoms: Vec<Atom>,
+    ) -> Self {
+        Self {
+            receiver: receiver.into(),
+            selector: selector.into(),
+            atoms,
+        }
+    }
+
+    /// Converts a message received from pd, `None` for the ones which are not sent to a receiver like midi and prints.
+    pub fn from_pd_message(message: &PdMessage) -> Option<Self> {
+        let message = match message {
+            PdMessage::Bang { source } => Self::new(source, "bang", vec![]),
+            PdMessage::Float { source, value } => Self::new(source, "float", vec![(*value).into()]),
+            PdMessage::Double { source, value } => {
+                Self::new(source, "float", vec![(*value).into()])
+            }
+            PdMessage::Symbol { source, symbol } => {
+                Self::new(source, "symbol", vec![symbol.into()])
+            }
+            PdMessage::List { source, list } => Self::new(source, "list", list.clone()),
+            PdMessage::Message {
+                source,
+                selector,
+                list,
+            } => Self::new(source, selector, list.clone()),
+            PdMessage::Print(_)
+            | PdMessage::MidiNoteOn { .. }
+            | PdMessage::MidiControlChange { .. }
+            | PdMessage::MidiProgramChange { .. }
+            | PdMessage::MidiPitchBend { .. }
+            | PdMessage::MidiAfterTouch { .. }
+            | PdMessage::MidiPolyAfterTouch { .. }
+            | PdMessage::MidiByte { .. } => return None,
+        };
+        Some(message)
+    }
+}
+
+/// An identifier for a patch which is opened in a [`Pd`](crate::Pd) instance.
+///
+/// Identifiers are unique for the lifetime of the [`Pd`](crate::Pd) instance which opened the patch and are never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PatchId(pub(crate) u64);
+
+impl PatchId {
+    /// Returns the inner value of the identifier.
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl core::fmt::Display for PatchId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "patch#{}", self.0)
+    }
+}
+
+/// Information about a patch which is open in a [`Pd`](crate::Pd) instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchInfo {
+    /// The identifier of the patch.
+    pub id: PatchId,
+    /// The `$0` of the patch, unique for every open patch.
+    pub dollar_zero: i32,
+    /// The path the patch was opened from, `None` if it is evaluated from a string or opened from a bundle.
+    pub path: Option<PathBuf>,
+    /// The objects pd couldn't create while the patch is opened or last reloaded.
+    pub failed_objects: Vec<FailedObject>,
+}
+
+/// An object which pd couldn't create while opening a patch, e.g. for a typo or a missing external.
+///
+/// Returned from [`failed_objects`](crate::functions::failed_objects) and kept for every open patch,
+/// see [`Pd::failed_objects`](crate::Pd::failed_objects).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedObject {
+    /// The text of the object as it is typed in its box.
+    pub text: String,
+    /// The horizontal position of the object in its canvas.
+    pub x: i32,
+    /// The vertical position of the object in its canvas.
+    pub y: i32,
+    /// The texts of the subpatches and the abstractions the object is in, from the outermost one.
+    ///
+    /// Empty for the objects in the patch itself.
+    pub canvases: Vec<String>,
+    /// The lines pd printed while it tried to create the object,
+    /// e.g. what the constructor of its class printed and the `... couldn't create` of pd.
+    ///
+    /// Empty if the output of pd is not captured while the patch is opened,
+    /// as for [`failed_objects`](crate::functions::failed_objects).
+    pub errors: Vec<String>,
+}
+
+/// A watched patch which is reopened because its file changed.
+///
+/// Returned from [`Pd::reload_changed_patches`](crate::Pd::reload_changed_patches).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatchReload {
+    /// The identifier of the patch, which stays the same through reloads.
+    pub id: PatchId,
+    /// The `$0` of the patch before it is reloaded.
+    pub previous_dollar_zero: i32,
+    /// The `$0` of the reloaded patch.
+    pub dollar_zero: i32,
+}
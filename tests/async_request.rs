@@ -0,0 +1,74 @@
+#![cfg(feature = "async")]
+#![allow(clippy::restriction)]
+
+use std::time::Duration;
+
+use libpd_rs::{error::RequestError, types::AddressedMessage, Atom, Pd};
+
+#[tokio::test]
+async fn async_request() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r query;
+    #X obj 20 50 + 1;
+    #X obj 20 80 s reply;
+    #X connect 0 0 1 0;
+    #X connect 1 0 2 0;
+        "#,
+    )
+    .unwrap();
+
+    // The reply is delivered when the queue is drained, the future only waits for it.
+    let query = String::from("query");
+    let reply = pd.request(
+        &query,
+        &[Atom::Float(41.0)],
+        "reply",
+        Duration::from_secs(1),
+    );
+    ctx.receive_messages_from_pd();
+    assert_eq!(
+        reply.await.unwrap(),
+        AddressedMessage::new("reply", "float", vec![Atom::Float(42.0)])
+    );
+
+    let mut stream = pd.stream_from("reply").unwrap();
+    assert_eq!(stream.source(), "reply");
+    pd.send_double_to("query", 1.0).unwrap();
+    pd.send_double_to("query", 2.0).unwrap();
+    ctx.receive_messages_from_pd();
+    assert_eq!(stream.recv().await.unwrap().atoms, vec![Atom::Float(2.0)]);
+    assert_eq!(stream.recv().await.unwrap().atoms, vec![Atom::Float(3.0)]);
+
+    let silence = pd.request("query", &[], "silence", Duration::from_millis(10));
+    ctx.receive_messages_from_pd();
+    assert!(matches!(silence.await, Err(RequestError::Timeout { .. })));
+
+    // The sources of the requests which are over are unsubscribed when the queues are drained,
+    // the ones with live streams stay subscribed without being listed in the subscriptions.
+    let other = pd.stream_from("other").unwrap();
+    ctx.receive_messages_from_pd();
+    assert!(!pd.source_to_listen_from_exists("silence").unwrap());
+    assert!(pd.source_to_listen_from_exists("reply").unwrap());
+    assert!(pd.source_to_listen_from_exists("other").unwrap());
+    assert!(pd.subscriptions.is_empty());
+
+    drop(other);
+    ctx.receive_messages_from_pd();
+    assert!(!pd.source_to_listen_from_exists("other").unwrap());
+
+    // Subscribing to sources keeps them after their streams are gone.
+    let many = pd.stream_from("many").unwrap();
+    pd.subscribe_to_many(&["many", "reply"]).unwrap();
+    drop(many);
+    drop(stream);
+    ctx.receive_messages_from_pd();
+    assert!(pd.subscriptions.contains_key("many"));
+    assert!(pd.subscriptions.contains_key("reply"));
+    assert!(pd.source_to_listen_from_exists("many").unwrap());
+    assert!(pd.source_to_listen_from_exists("reply").unwrap());
+}
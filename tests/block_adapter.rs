@@ -0,0 +1,38 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::Pd;
+
+#[test]
+fn block_adapter() {
+    let mut pd = Pd::init_and_configure(1, 1, 44100).unwrap();
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 adc~;
+    #X obj 20 50 dac~;
+    #X connect 0 0 1 0;
+        "#,
+    )
+    .unwrap();
+    pd.dsp_on().unwrap();
+
+    let mut adapter = pd.block_adapter();
+    let latency = adapter.latency();
+
+    // Sizes which are not multiples of the block size, as hosts often use them.
+    let input: Vec<f32> = (1..=3000).map(|sample| sample as f32).collect();
+    let mut output = vec![0.0_f32; input.len()];
+    let mut offset = 0;
+    for size in [441, 480, 1, 63, 1024, 991] {
+        adapter.process_float(
+            &input[offset..offset + size],
+            &mut output[offset..offset + size],
+        );
+        offset += size;
+    }
+    assert_eq!(offset, input.len());
+
+    // The output is the input delayed by the latency of the adapter.
+    assert!(output[..latency].iter().all(|sample| *sample == 0.0));
+    assert_eq!(&output[latency..], &input[..input.len() - latency]);
+}
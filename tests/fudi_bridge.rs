@@ -0,0 +1,57 @@
+#![allow(clippy::restriction)]
+
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+
+use libpd_rs::{fudi::FudiBridge, Pd};
+
+#[test]
+fn fudi_bridge() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r to_pd;
+    #X obj 20 50 s from_pd;
+    #X connect 0 0 1 0;
+        "#,
+    )
+    .unwrap();
+    let _receiver = pd.start_listening_from("from_pd").unwrap();
+
+    let bridge = FudiBridge::tcp(&mut pd, "127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(bridge.local_addr()).unwrap();
+    client
+        .set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+
+    // The message is split in two writes, like it may arrive from the network.
+    client.write_all(b"to_pd 1 2 ").unwrap();
+    client.write_all(b"3;\nto_pd set a\\ b;\n").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut forwarded = 0;
+    while forwarded < 2 && Instant::now() < deadline {
+        forwarded += bridge.forward(&pd).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(forwarded, 2);
+
+    // Messages from pd are collected while its queues are drained and sent out on the next forward.
+    ctx.receive_messages_from_pd();
+    bridge.forward(&pd).unwrap();
+
+    let mut received = String::new();
+    let mut buffer = [0_u8; 256];
+    while received.matches(';').count() < 2 && Instant::now() < deadline {
+        if let Ok(read) = client.read(&mut buffer) {
+            received.push_str(std::str::from_utf8(&buffer[..read]).unwrap());
+        }
+    }
+    assert_eq!(received, "from_pd list 1 2 3;\nfrom_pd set a\\ b;\n");
+}
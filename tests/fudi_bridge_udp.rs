@@ -0,0 +1,59 @@
+#![allow(clippy::restriction)]
+
+use std::{
+    net::UdpSocket,
+    time::{Duration, Instant},
+};
+
+use libpd_rs::{
+    fudi::{FudiBridge, FudiRouting},
+    Pd,
+};
+
+#[test]
+fn fudi_bridge_udp() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r to_pd;
+    #X obj 20 50 s from_pd;
+    #X connect 0 0 1 0;
+        "#,
+    )
+    .unwrap();
+    let _receiver = pd.start_listening_from("from_pd").unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_read_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+    // Every message is sent to the same receiver, so they don't start with its name.
+    let bridge = FudiBridge::udp(&mut pd, "127.0.0.1:0", peer.local_addr().unwrap())
+        .unwrap()
+        .routing(FudiRouting::Receiver("to_pd".to_owned()));
+
+    peer.send_to(b"1 2 3;\n", bridge.local_addr()).unwrap();
+    peer.send_to(b"set a\\ b;\n", bridge.local_addr()).unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut forwarded = 0;
+    while forwarded < 2 && Instant::now() < deadline {
+        forwarded += bridge.forward(&pd).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(forwarded, 2);
+
+    ctx.receive_messages_from_pd();
+    bridge.forward(&pd).unwrap();
+
+    let mut received = String::new();
+    let mut buffer = [0_u8; 256];
+    while received.matches(';').count() < 2 && Instant::now() < deadline {
+        if let Ok((read, _)) = peer.recv_from(&mut buffer) {
+            received.push_str(std::str::from_utf8(&buffer[..read]).unwrap());
+        }
+    }
+    assert_eq!(received, "from_pd list 1 2 3;\nfrom_pd set a\\ b;\n");
+}
@@ -0,0 +1,91 @@
+#![allow(clippy::restriction)]
+
+use std::sync::mpsc;
+
+use libpd_rs::{functions::block_size, types::PdMessage, Pd};
+
+#[test]
+fn message_receiver() {
+    let sample_rate = 44100;
+    let output_channels = 2;
+
+    let mut pd = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.open_patch("tests/patches/echo.pd").unwrap();
+
+    let messages = pd.message_receiver();
+    let dropped = pd.message_receiver();
+    drop(dropped);
+
+    let bang_receiver = pd.start_listening_from("bang_from_pd").unwrap();
+    let float_receiver = pd.start_listening_from("float_from_pd").unwrap();
+    let symbol_receiver = pd.start_listening_from("symbol_from_pd").unwrap();
+
+    pd.dsp_on().unwrap();
+
+    let (tx, rx) = mpsc::channel::<()>();
+
+    let handle = std::thread::spawn(move || {
+        // Mimic audio callback buffers.
+        let input_buffer = [0.0f32; 512];
+        let mut output_buffer = [0.0f32; 1024];
+
+        // Run pd
+        loop {
+            // Mimic an audio callback.
+            let approximate_buffer_duration =
+                (output_buffer.len() as f32 / sample_rate as f32) * 1000.0;
+            std::thread::sleep(std::time::Duration::from_millis(
+                approximate_buffer_duration as u64,
+            ));
+
+            ctx.receive_messages_from_pd();
+            ctx.receive_midi_messages_from_pd();
+            let ticks = output_buffer.len() as i32 / (block_size() * output_channels);
+            ctx.process_float(ticks, &input_buffer, &mut output_buffer);
+            match rx.try_recv() {
+                Ok(_) => break,
+                _ => continue,
+            }
+        }
+    });
+
+    pd.send_bang_to("bang_from_rust").unwrap();
+    pd.send_double_to("float_from_rust", 42.0).unwrap();
+    pd.send_symbol_to("symbol_from_rust", "hello").unwrap();
+    pd.send_note_on(0, 60, 100).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    // Stop pd.
+    tx.send(()).unwrap();
+    handle.join().unwrap();
+
+    let received: Vec<PdMessage> = messages.try_iter().collect();
+    assert_eq!(
+        received,
+        vec![
+            PdMessage::Bang {
+                source: "bang_from_pd".to_owned()
+            },
+            PdMessage::Double {
+                source: "float_from_pd".to_owned(),
+                value: 42.0
+            },
+            PdMessage::Symbol {
+                source: "symbol_from_pd".to_owned(),
+                symbol: "hello".to_owned()
+            },
+            PdMessage::MidiNoteOn {
+                channel: 0,
+                pitch: 60,
+                velocity: 100
+            },
+        ]
+    );
+
+    pd.stop_listening_from(bang_receiver);
+    pd.stop_listening_from(float_receiver);
+    pd.stop_listening_from(symbol_receiver);
+    pd.close_patch().unwrap();
+}
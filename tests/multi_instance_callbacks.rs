@@ -0,0 +1,87 @@
+#![allow(clippy::restriction)]
+
+use std::sync::{mpsc, Arc, Mutex};
+
+use libpd_rs::{functions::block_size, Pd};
+
+#[test]
+fn multi_instance_callbacks() {
+    let sample_rate = 44100;
+    let output_channels = 2;
+
+    let first_floats: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(vec![]));
+    let second_floats: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(vec![]));
+
+    let mut first = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
+    let mut second = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
+    let first_ctx = first.audio_context();
+    let second_ctx = second.audio_context();
+
+    first.open_patch("tests/patches/echo.pd").unwrap();
+    second.open_patch("tests/patches/echo.pd").unwrap();
+
+    let floats_to_fill = first_floats.clone();
+    first
+        .on_float(move |source, value| {
+            assert_eq!(source, "float_from_pd");
+            floats_to_fill.lock().unwrap().push(value);
+        })
+        .unwrap();
+    let floats_to_fill = second_floats.clone();
+    second
+        .on_float(move |source, value| {
+            assert_eq!(source, "float_from_pd");
+            floats_to_fill.lock().unwrap().push(value);
+        })
+        .unwrap();
+
+    let first_receiver = first.start_listening_from("float_from_pd").unwrap();
+    let second_receiver = second.start_listening_from("float_from_pd").unwrap();
+
+    first.dsp_on().unwrap();
+    second.dsp_on().unwrap();
+
+    let (tx, rx) = mpsc::channel::<()>();
+
+    let handle = std::thread::spawn(move || {
+        // Mimic audio callback buffers.
+        let input_buffer = [0.0f32; 512];
+        let mut output_buffer = [0.0f32; 1024];
+
+        // Run both instances from the same thread.
+        loop {
+            let approximate_buffer_duration =
+                (output_buffer.len() as f32 / sample_rate as f32) * 1000.0;
+            std::thread::sleep(std::time::Duration::from_millis(
+                approximate_buffer_duration as u64,
+            ));
+
+            let ticks = output_buffer.len() as i32 / (block_size() * output_channels);
+            first_ctx.receive_messages_from_pd();
+            first_ctx.process_float(ticks, &input_buffer, &mut output_buffer);
+            second_ctx.receive_messages_from_pd();
+            second_ctx.process_float(ticks, &input_buffer, &mut output_buffer);
+            match rx.try_recv() {
+                Ok(_) => break,
+                _ => continue,
+            }
+        }
+    });
+
+    first.send_float_to("float_from_rust", 1.0).unwrap();
+    first.send_float_to("float_from_rust", 2.0).unwrap();
+    second.send_float_to("float_from_rust", 10.0).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    // Stop pd.
+    tx.send(()).unwrap();
+    handle.join().unwrap();
+
+    assert_eq!(*first_floats.lock().unwrap(), vec![1.0, 2.0]);
+    assert_eq!(*second_floats.lock().unwrap(), vec![10.0]);
+
+    first.stop_listening_from(first_receiver);
+    second.stop_listening_from(second_receiver);
+    first.close_patch().unwrap();
+    second.close_patch().unwrap();
+}
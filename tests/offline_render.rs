@@ -0,0 +1,71 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::RenderError,
+    functions::block_size,
+    render::{OfflineRenderer, WavSampleFormat},
+    Pd,
+};
+
+#[test]
+fn offline_render() {
+    let sample_rate = 44100;
+    let output_channels = 2;
+
+    let mut pd = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
+    pd.open_patch("tests/patches/sine.pd").unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let float_path = dir.path().join("sine_float.wav");
+    let int_path = dir.path().join("sine_int.wav");
+
+    // Rendering without dsp would only produce silence.
+    assert!(matches!(
+        OfflineRenderer::new(&pd).render_ticks(&float_path, 1),
+        Err(RenderError::DspInactive)
+    ));
+
+    pd.dsp_on().unwrap();
+
+    OfflineRenderer::new(&pd)
+        .render_seconds(&float_path, 1.0)
+        .unwrap();
+    OfflineRenderer::new(&pd)
+        .sample_format(WavSampleFormat::Int16)
+        .render_ticks(&int_path, 100)
+        .unwrap();
+
+    let reader = hound::WavReader::open(&float_path).unwrap();
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, 44100);
+    assert_eq!(spec.bits_per_sample, 32);
+    let ticks = (44100_f64 / block_size() as f64).ceil() as u32;
+    assert_eq!(reader.duration(), ticks * block_size() as u32);
+    let peak = reader
+        .into_samples::<f32>()
+        .map(|sample| sample.unwrap().abs())
+        .fold(0.0_f32, f32::max);
+    // The sine in the patch is scaled by 0.1.
+    assert!(peak > 0.09 && peak <= 0.1001);
+
+    let reader = hound::WavReader::open(&int_path).unwrap();
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    assert_eq!(reader.duration(), 100 * block_size() as u32);
+    assert!(reader
+        .into_samples::<i16>()
+        .any(|sample| sample.unwrap() != 0));
+
+    // The input file needs to match the instance.
+    assert!(matches!(
+        OfflineRenderer::new(&pd)
+            .input_wav(&int_path)
+            .render_ticks(dir.path().join("unused.wav"), 1),
+        Err(RenderError::InputChannelMismatch {
+            expected: 0,
+            found: 2
+        })
+    ));
+
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,79 @@
+#![allow(clippy::restriction)]
+
+use std::{
+    net::UdpSocket,
+    time::{Duration, Instant},
+};
+
+use libpd_rs::{
+    osc::{OscArg, OscBridge, OscMessage, OscPacket},
+    Pd,
+};
+
+#[test]
+fn osc_bridge() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r synth_cutoff;
+    #X obj 20 50 s level;
+    #X obj 200 20 r voice;
+    #X obj 200 50 s voice_out;
+    #X connect 0 0 1 0;
+    #X connect 2 0 3 0;
+        "#,
+    )
+    .unwrap();
+
+    let controller = UdpSocket::bind("127.0.0.1:0").unwrap();
+    controller
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let bridge = OscBridge::udp(&mut pd, "127.0.0.1:0", controller.local_addr().unwrap())
+        .unwrap()
+        .route("/meter", "level")
+        .route("/voice/*", "voice")
+        .route("/out/*", "voice_out");
+    // Only the sources which are sent out, subscribing to `voice` would echo the messages which arrive.
+    pd.subscribe_to_many(&["level", "voice_out"]).unwrap();
+
+    // `/synth/cutoff` has no rule, it is sent to `synth_cutoff` as a float.
+    let cutoff = OscMessage::new("/synth/cutoff", vec![OscArg::Float(0.5)]);
+    let note = OscMessage::new("/voice/note", vec![OscArg::Int(60), OscArg::Int(100)]);
+    for message in [cutoff, note] {
+        controller
+            .send_to(&OscPacket::Message(message).encode(), bridge.local_addr())
+            .unwrap();
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut forwarded = 0;
+    while forwarded < 2 && Instant::now() < deadline {
+        forwarded += bridge.forward(&pd).unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(forwarded, 2);
+
+    ctx.receive_messages_from_pd();
+    bridge.forward(&pd).unwrap();
+
+    let mut received = Vec::new();
+    let mut buffer = [0_u8; 1024];
+    for _ in 0..2 {
+        let (read, _) = controller.recv_from(&mut buffer).unwrap();
+        received.push(OscPacket::decode(&buffer[..read]).unwrap());
+    }
+    assert_eq!(
+        received,
+        vec![
+            OscPacket::Message(OscMessage::new("/meter", vec![OscArg::Float(0.5)])),
+            OscPacket::Message(OscMessage::new(
+                "/out/note",
+                vec![OscArg::Float(60.0), OscArg::Float(100.0)]
+            )),
+        ]
+    );
+}
@@ -0,0 +1,37 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{patch::PatchBuilder, types::PdMessage, Pd};
+
+#[test]
+fn patch_builder() {
+    let mut builder = PatchBuilder::new();
+    let receive = builder.obj("r builder_in");
+    let add = builder.subpatch("add", |inner| {
+        let inlet = inner.obj("inlet");
+        let add = inner.obj("+ 1");
+        let outlet = inner.obj("outlet");
+        inner.connect(inlet, 0, add, 0).connect(add, 0, outlet, 0);
+    });
+    let send = builder.obj("s builder_out");
+    builder.connect(receive, 0, add, 0).connect(add, 0, send, 0);
+
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+    let messages = pd.message_receiver();
+    let id = pd.eval_patch(builder.build().to_string()).unwrap();
+    let receiver_handle = pd.start_listening_from("builder_out").unwrap();
+
+    pd.send_double_to("builder_in", 41.0).unwrap();
+    ctx.receive_messages_from_pd();
+
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Double {
+            source: "builder_out".to_owned(),
+            value: 42.0
+        }]
+    );
+
+    pd.stop_listening_from(receiver_handle);
+    pd.close_patch_by_id(id).unwrap();
+}
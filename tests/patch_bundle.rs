@@ -0,0 +1,130 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::{PatchLifeCycleError, PdError},
+    include_patch_bundle,
+    patch::PatchBundle,
+    types::PdMessage,
+    Pd,
+};
+
+#[test]
+fn patch_bundle() {
+    let bundle = include_patch_bundle!("patches/bundle", ["main.pd", "adder.pd", "lib/twice.pd"]);
+    assert_eq!(bundle.len(), 3);
+
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+    let messages = pd.message_receiver();
+    // A search path of the user, which is kept while bundles come and go.
+    pd.add_path_to_search_paths("tests/patches").unwrap();
+
+    assert!(matches!(
+        pd.open_bundle(&bundle, "missing.pd"),
+        Err(PdError::PatchLifeCycleError(
+            PatchLifeCycleError::FileNotInBundle(_)
+        ))
+    ));
+
+    let id = pd.open_bundle(&bundle, "main.pd").unwrap();
+    assert!(pd.open_patches()[0].path.is_none());
+    let receiver_handle = pd.start_listening_from("bundle_out").unwrap();
+
+    // 1 + 1 in adder.pd, then * 2 in lib/twice.pd.
+    pd.send_double_to("bundle_in", 1.0).unwrap();
+    ctx.receive_messages_from_pd();
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Double {
+            source: "bundle_out".to_owned(),
+            value: 4.0
+        }]
+    );
+
+    // Another bundle with its own abstraction of the same name.
+    let other = PatchBundle::new()
+        .with_file(
+            "other.pd",
+            "#N canvas 0 50 450 300 12;\n#X obj 20 20 r other_in;\n#X obj 20 50 adder 10;\n\
+             #X obj 20 80 s bundle_out;\n#X connect 0 0 1 0;\n#X connect 1 0 2 0;\n",
+        )
+        .with_file(
+            "adder.pd",
+            "#N canvas 0 50 450 300 12;\n#X obj 20 20 inlet;\n#X obj 20 50 - \\$1;\n\
+             #X obj 20 80 outlet;\n#X connect 0 0 1 0;\n#X connect 1 0 2 0;\n",
+        );
+    let other_id = pd.open_bundle(&other, "other.pd").unwrap();
+    pd.send_double_to("other_in", 1.0).unwrap();
+    pd.send_double_to("bundle_in", 1.0).unwrap();
+    ctx.receive_messages_from_pd();
+    let values: Vec<f64> = messages
+        .try_iter()
+        .filter_map(|message| match message {
+            PdMessage::Double { value, .. } => Some(value),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, vec![-9.0, 4.0]);
+    pd.stop_listening_from(receiver_handle);
+    pd.close_patch_by_id(other_id).unwrap();
+    pd.close_patch_by_id(id).unwrap();
+
+    // Other files of the bundle are found through the search paths,
+    // here from an abstraction in a subdirectory which doesn't have the file next to it.
+    let with_data = PatchBundle::new()
+        .with_file(
+            "main.pd",
+            "#N canvas 0 50 450 300 12;\n#X obj 20 20 lib/finder;\n",
+        )
+        .with_file(
+            "lib/finder.pd",
+            "#N canvas 0 50 450 300 12;\n#X obj 20 20 r find;\n#X obj 20 50 file which;\n\
+             #X obj 20 80 s found;\n#X connect 0 0 1 0;\n#X connect 1 0 2 0;\n",
+        )
+        .with_file("data/notes.txt", "60 64 67;\n");
+    let data_id = pd.open_bundle(&with_data, "main.pd").unwrap();
+    let found_handle = pd.start_listening_from("found").unwrap();
+    pd.send_symbol_to("find", "data/notes.txt").unwrap();
+    ctx.receive_messages_from_pd();
+    let found: Vec<PdMessage> = messages.try_iter().collect();
+    assert!(
+        matches!(
+            found.as_slice(),
+            [PdMessage::List { list, .. }] if list[0].to_string().ends_with("data/notes.txt")
+        ),
+        "{found:?}"
+    );
+    pd.close_patch_by_id(data_id).unwrap();
+
+    // The directory of the closed bundle is gone from the search paths, the path of the user is still there.
+    let finder = pd
+        .eval_patch(
+            "#N canvas 0 50 450 300 12;\n#X obj 20 20 r find;\n#X obj 20 50 file which;\n\
+             #X obj 20 80 s found;\n#X connect 0 0 1 0;\n#X connect 1 0 2 0;\n",
+        )
+        .unwrap();
+    pd.send_symbol_to("find", "data/notes.txt").unwrap();
+    pd.send_symbol_to("find", "sine.pd").unwrap();
+    ctx.receive_messages_from_pd();
+    let found: Vec<PdMessage> = messages.try_iter().collect();
+    assert!(
+        matches!(
+            found.as_slice(),
+            [PdMessage::List { list, .. }] if list[0].to_string().ends_with("sine.pd")
+        ),
+        "{found:?}"
+    );
+    pd.stop_listening_from(found_handle);
+    pd.close_patch_by_id(finder).unwrap();
+
+    // Paths can not escape the bundle.
+    let escaping: PatchBundle = [("../outside.pd", "#N canvas 0 50 450 300 12;\n")]
+        .into_iter()
+        .collect();
+    assert!(matches!(
+        pd.open_bundle(&escaping, "../outside.pd"),
+        Err(PdError::PatchLifeCycleError(
+            PatchLifeCycleError::InvalidPathInBundle(_)
+        ))
+    ));
+}
@@ -0,0 +1,84 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{types::FailedObject, Pd};
+
+#[test]
+fn patch_failed_objects() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let clean = pd
+        .eval_patch(
+            r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 osc~ 440;
+        "#,
+        )
+        .unwrap();
+    assert_eq!(pd.failed_objects(clean), Some(&[][..]));
+
+    let broken = pd
+        .eval_patch(
+            r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 no_such_object 1 2;
+    #X obj 20 60 osc~ 440;
+    #X text 20 100 just a comment;
+    #X obj 20 140 expr 1 +;
+    #N canvas 0 50 450 300 voice 0;
+    #X obj 10 30 typo~;
+    #X restore 200 20 pd voice;
+        "#,
+        )
+        .unwrap();
+
+    let failed = pd.failed_objects(broken).unwrap();
+    assert_eq!(
+        failed,
+        &[
+            FailedObject {
+                text: "no_such_object 1 2".to_owned(),
+                x: 20,
+                y: 20,
+                canvases: vec![],
+                errors: failed[0].errors.clone(),
+            },
+            FailedObject {
+                text: "expr 1 +".to_owned(),
+                x: 20,
+                y: 140,
+                canvases: vec![],
+                errors: failed[1].errors.clone(),
+            },
+            FailedObject {
+                text: "typo~".to_owned(),
+                x: 10,
+                y: 30,
+                canvases: vec!["pd voice".to_owned()],
+                errors: failed[2].errors.clone(),
+            },
+        ]
+    );
+    // Every object gets what pd printed while creating it, which ends with the text of the object and the error of pd.
+    for object in failed {
+        assert!(object.errors.len() >= 2, "{object:?}");
+        assert!(object.errors[object.errors.len() - 2].ends_with(&object.text));
+        assert_eq!(object.errors.last().unwrap(), "error: ... couldn't create");
+    }
+    assert_eq!(failed[0].errors.len(), 2);
+    assert_eq!(failed[2].errors.len(), 2);
+    // The errors the constructor printed come before the ones of pd.
+    assert!(failed[1]
+        .errors
+        .contains(&"error: expr: syntax error".to_owned()));
+
+    // The patch is open and the failures are also listed with the other patch infos.
+    let info = pd
+        .open_patches()
+        .into_iter()
+        .find(|info| info.id == broken)
+        .unwrap();
+    assert_eq!(info.failed_objects, failed);
+
+    pd.close_patch_by_id(broken).unwrap();
+    assert!(pd.failed_objects(broken).is_none());
+}
@@ -0,0 +1,38 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    patch::{Node, Patch, Token},
+    Pd,
+};
+
+#[test]
+fn patch_round_trip() {
+    for name in ["simple", "echo", "sine", "array_sketch_pad"] {
+        let source = std::fs::read_to_string(format!("tests/patches/{name}.pd")).unwrap();
+        let patch: Patch = source.parse().unwrap();
+        assert_eq!(
+            patch.to_string(),
+            source,
+            "{name}.pd is not written back as is"
+        );
+    }
+
+    // Transform a patch before evaluating it.
+    let source = std::fs::read_to_string("tests/patches/sine.pd").unwrap();
+    let mut patch = Patch::parse(&source).unwrap();
+    for element in &mut patch.canvas.elements {
+        if let Node::Object(object) = &mut element.node {
+            if object.class() == Some("osc~") {
+                object.args = vec![Token::from("osc~"), Token::from(880)];
+            }
+        }
+    }
+    let written = patch.to_string();
+    assert!(written.contains("osc~ 880;"));
+    assert_eq!(Patch::parse(&written).unwrap(), patch);
+
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let id = pd.eval_patch(&written).unwrap();
+    assert!(pd.is_patch_open(id));
+    pd.close_patch_by_id(id).unwrap();
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "testing")]
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::TestingError,
+    schedule::ScheduledMessage,
+    testing::{golden_path, PatchTest, UPDATE_GOLDEN_ENV},
+};
+
+#[test]
+fn patch_testing() {
+    let mut test = PatchTest::new("tests/patches/level.pd", 0, 2, 44100).unwrap();
+    test.pd_mut().subscribe_to("level_out").unwrap();
+    test.send_at(
+        2,
+        ScheduledMessage::Float {
+            receiver: "level".to_owned(),
+            value: 0.5,
+        },
+    );
+
+    let output = test.run(4).unwrap();
+    output
+        .compare_audio(golden_path("level.wav"), 1e-6)
+        .unwrap();
+    output
+        .compare_messages(golden_path("level.json"), 1e-9)
+        .unwrap();
+
+    // The next run continues from the tick the last one stopped at.
+    test.send_at(
+        0,
+        ScheduledMessage::Float {
+            receiver: "level".to_owned(),
+            value: 0.25,
+        },
+    );
+    let mut output = test.run(1).unwrap();
+    assert_eq!(output.messages.len(), 1);
+    assert_eq!(output.messages[0].tick, 4);
+    assert!(output.audio.iter().all(|sample| *sample == 0.25));
+
+    // The mismatches are only reported when the golden files are not being updated,
+    // this also keeps the goldens from being overwritten with this run.
+    std::env::remove_var(UPDATE_GOLDEN_ENV);
+    assert!(matches!(
+        output.compare_audio(golden_path("level.wav"), 1e-6),
+        Err(TestingError::LengthMismatch { .. })
+    ));
+    assert!(matches!(
+        output.compare_messages(golden_path("level.json"), 1e-9),
+        Err(TestingError::MessageMismatch { index: 0, .. })
+    ));
+    assert!(matches!(
+        output.compare_audio(golden_path("missing.wav"), 1e-6),
+        Err(TestingError::GoldenMissing(_))
+    ));
+
+    // A value which turns the updates off is the same as no value.
+    std::env::set_var(UPDATE_GOLDEN_ENV, "0");
+    assert!(matches!(
+        output.compare_audio(golden_path("missing.wav"), 1e-6),
+        Err(TestingError::GoldenMissing(_))
+    ));
+    std::env::remove_var(UPDATE_GOLDEN_ENV);
+
+    // A NaN never matches, whatever the tolerance.
+    let golden = std::env::temp_dir().join("libpd_rs_patch_testing_nan.wav");
+    output.save_audio(&golden).unwrap();
+    output.audio[1] = f32::NAN;
+    assert!(matches!(
+        output.compare_audio(&golden, f32::INFINITY),
+        Err(TestingError::AudioMismatch {
+            frame: 0,
+            channel: 1,
+            ..
+        })
+    ));
+    std::fs::remove_file(golden).unwrap();
+}
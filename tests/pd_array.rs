@@ -0,0 +1,53 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{error::ArrayError, Pd};
+
+#[test]
+fn pd_array() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+
+    assert!(matches!(
+        pd.array("not_exists"),
+        Err(ArrayError::FailedToFindArray)
+    ));
+
+    let sketch_pad = pd.array("sketch_pad").unwrap();
+    assert_eq!(sketch_pad.name(), "sketch_pad");
+    assert_eq!(sketch_pad.len().unwrap(), 100);
+
+    sketch_pad.resize_preserving(4).unwrap();
+    sketch_pad.fill(0.5).unwrap();
+    sketch_pad.write_from(2, &[1.0, 2.0]).unwrap();
+    assert_eq!(sketch_pad.to_vec().unwrap(), vec![0.5, 0.5, 1.0, 2.0]);
+
+    // Existing elements are kept, new ones are zero.
+    sketch_pad.resize_preserving(6).unwrap();
+    assert_eq!(sketch_pad.len().unwrap(), 6);
+    assert_eq!(
+        sketch_pad.to_vec().unwrap(),
+        vec![0.5, 0.5, 1.0, 2.0, 0.0, 0.0]
+    );
+
+    let mut read = [0.0_f32; 3];
+    sketch_pad.read_into(1..4, &mut read).unwrap();
+    assert_eq!(read, [0.5, 1.0, 2.0]);
+
+    assert!(matches!(
+        sketch_pad.read_into(4..7, &mut read),
+        Err(ArrayError::OutOfBounds)
+    ));
+    assert!(matches!(
+        sketch_pad.read_into(0..2, &mut read),
+        Err(ArrayError::LengthMismatch {
+            expected: 2,
+            found: 3
+        })
+    ));
+    assert!(matches!(
+        sketch_pad.write_from(5, &[1.0, 1.0]),
+        Err(ArrayError::OutOfBounds)
+    ));
+
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,47 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{array::ArrayChange, Pd};
+
+#[test]
+fn pd_array_watch() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+
+    let sketch_pad = pd.array("sketch_pad").unwrap();
+    sketch_pad.resize_preserving(10).unwrap();
+    sketch_pad.fill(0.0).unwrap();
+    let mut watcher = sketch_pad.watch(4);
+
+    // Everything is dirty at first.
+    assert_eq!(
+        watcher.poll().unwrap(),
+        ArrayChange {
+            len: 10,
+            dirty: vec![0..10]
+        }
+    );
+    assert!(watcher.poll().unwrap().is_clean());
+
+    // Adjacent chunks merge, the last chunk is cut at the end of the array.
+    watcher.array().write_from(3, &[1.0, 1.0]).unwrap();
+    watcher.array().write_from(9, &[1.0]).unwrap();
+    assert_eq!(watcher.poll().unwrap().dirty, vec![0..10]);
+
+    watcher.array().write_from(5, &[2.0]).unwrap();
+    assert_eq!(watcher.poll().unwrap().dirty, vec![4..8]);
+
+    // Writing the same values is not a change.
+    watcher.array().write_from(5, &[2.0]).unwrap();
+    assert!(watcher.poll().unwrap().is_clean());
+
+    watcher.array().resize_preserving(14).unwrap();
+    assert_eq!(
+        watcher.poll().unwrap(),
+        ArrayChange {
+            len: 14,
+            dirty: vec![8..14]
+        }
+    );
+
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,67 @@
+#![allow(clippy::restriction)]
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use libpd_rs::{
+    array::{load_wav_channels, load_wav_channels_resampled},
+    error::ArrayError,
+    Pd,
+};
+
+#[test]
+fn pd_array_wav() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 table left;
+    #X obj 20 50 table right;
+        "#,
+    )
+    .unwrap();
+    let left = pd.array("left").unwrap();
+    let right = pd.array("right").unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let stereo_path = dir.path().join("stereo.wav");
+    let spec = WavSpec {
+        channels: 2,
+        sample_rate: 22050,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(&stereo_path, spec).unwrap();
+    for sample in [0.0_f32, 1.0, 0.5, -1.0, 1.0, 0.0] {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    right.load_wav(&stereo_path, 1).unwrap();
+    assert_eq!(right.to_vec().unwrap(), vec![1.0, -1.0, 0.0]);
+    assert!(matches!(
+        right.load_wav(&stereo_path, 2),
+        Err(ArrayError::ChannelOutOfRange {
+            channel: 2,
+            channels: 2
+        })
+    ));
+
+    load_wav_channels(&stereo_path, &[(0, &left), (1, &right)]).unwrap();
+    assert_eq!(left.to_vec().unwrap(), vec![0.0, 0.5, 1.0]);
+    assert_eq!(right.to_vec().unwrap(), vec![1.0, -1.0, 0.0]);
+
+    // The file is at half the sample rate of the instance.
+    left.load_wav_resampled(&stereo_path, 0).unwrap();
+    assert_eq!(left.to_vec().unwrap(), vec![0.0, 0.25, 0.5, 0.75, 1.0, 1.0]);
+    load_wav_channels_resampled(&stereo_path, &[(1, &right)]).unwrap();
+    assert_eq!(right.len().unwrap(), 6);
+
+    let mono_path = dir.path().join("mono.wav");
+    left.save_wav(&mono_path, 44100).unwrap();
+    let reader = hound::WavReader::open(&mono_path).unwrap();
+    assert_eq!(reader.spec().channels, 1);
+    assert_eq!(reader.spec().sample_rate, 44100);
+    let saved: Vec<f32> = reader.into_samples().map(Result::unwrap).collect();
+    assert_eq!(saved, left.to_vec().unwrap());
+
+    pd.close_patch().unwrap();
+}
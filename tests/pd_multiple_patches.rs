@@ -0,0 +1,53 @@
+use libpd_rs::Pd;
+
+#[test]
+fn multiple_patches() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let sine = pd.open_patch("tests/patches/sine.pd").unwrap();
+    let echo = pd.open_patch("tests/patches/echo.pd").unwrap();
+    let evaluated = pd
+        .eval_patch(
+            r#"
+    #N canvas 577 549 158 168 12;
+    #X obj 23 17 osc~ 220;
+        "#,
+        )
+        .unwrap();
+
+    assert_ne!(sine, echo);
+    assert_ne!(echo, evaluated);
+
+    let open = pd.open_patches();
+    assert_eq!(
+        open.iter().map(|patch| patch.id).collect::<Vec<_>>(),
+        vec![sine, echo, evaluated]
+    );
+    assert!(open[0].path.as_ref().unwrap().ends_with("sine.pd"));
+    assert!(open[2].path.is_none());
+
+    // Every patch has its own $0.
+    let sine_dollar_zero = pd.dollar_zero_of(sine).unwrap();
+    let echo_dollar_zero = pd.dollar_zero_of(echo).unwrap();
+    assert_ne!(sine_dollar_zero, echo_dollar_zero);
+    assert_eq!(
+        pd.dollar_zero().unwrap(),
+        pd.dollar_zero_of(evaluated).unwrap()
+    );
+
+    // Closing one leaves the others open.
+    pd.close_patch_by_id(echo).unwrap();
+    assert!(!pd.is_patch_open(echo));
+    assert!(pd.is_patch_open(sine));
+    assert!(pd.dollar_zero_of(echo).is_err());
+    assert!(pd.close_patch_by_id(echo).is_err());
+    assert_eq!(pd.open_patches().len(), 2);
+
+    // Ids are never reused.
+    let echo_again = pd.open_patch("tests/patches/echo.pd").unwrap();
+    assert_ne!(echo_again, echo);
+
+    pd.close_patch().unwrap();
+    assert!(pd.open_patches().is_empty());
+    assert!(pd.dollar_zero().is_err());
+}
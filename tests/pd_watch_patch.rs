@@ -0,0 +1,77 @@
+#![allow(clippy::restriction)]
+
+use std::{
+    fs,
+    time::{Duration, SystemTime},
+};
+
+use libpd_rs::{types::PdMessage, Pd};
+
+const PASS_THROUGH: &str = "#N canvas 0 50 450 300 12;
+#X obj 20 20 r watch_in;
+#X obj 20 50 s watch_out;
+#X connect 0 0 1 0;
+";
+
+const ADD_ONE: &str = "#N canvas 0 50 450 300 12;
+#X obj 20 20 r watch_in;
+#X obj 20 50 + 1;
+#X obj 20 80 s watch_out;
+#X connect 0 0 1 0;
+#X connect 1 0 2 0;
+";
+
+#[test]
+fn watch_patch() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("watched.pd");
+    fs::write(&path, PASS_THROUGH).unwrap();
+
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+    let messages = pd.message_receiver();
+
+    let id = pd.watch_patch(&path).unwrap();
+    pd.subscribe_to("watch_out").unwrap();
+    let dollar_zero = pd.dollar_zero_of(id).unwrap();
+
+    // Nothing changed yet.
+    assert!(pd.reload_changed_patches().is_empty());
+
+    pd.send_double_to("watch_in", 1.0).unwrap();
+    ctx.receive_messages_from_pd();
+
+    fs::write(&path, ADD_ONE).unwrap();
+    // Make sure the change is visible even on file systems with a coarse modification time.
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(2))
+        .unwrap();
+
+    let reloads = pd.reload_changed_patches();
+    assert_eq!(reloads.len(), 1);
+    let reload = reloads[0].as_ref().unwrap();
+    assert_eq!(reload.id, id);
+    assert_eq!(reload.previous_dollar_zero, dollar_zero);
+    assert_eq!(pd.dollar_zero_of(id).unwrap(), reload.dollar_zero);
+    assert!(pd.reload_changed_patches().is_empty());
+
+    pd.send_double_to("watch_in", 1.0).unwrap();
+    ctx.receive_messages_from_pd();
+
+    // The subscription keeps receiving from the reloaded patch.
+    let values: Vec<f64> = messages
+        .try_iter()
+        .filter_map(|message| match message {
+            PdMessage::Double { source, value } if source == "watch_out" => Some(value),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, vec![1.0, 2.0]);
+
+    pd.unwatch_patch(id);
+    pd.unsubscribe_from_all();
+    pd.close_patch_by_id(id).unwrap();
+}
@@ -0,0 +1,61 @@
+#![allow(clippy::restriction)]
+
+use std::sync::{Arc, Mutex};
+
+use libpd_rs::{
+    print::{PrintLevel, PrintRecord},
+    Pd,
+};
+
+#[test]
+fn print_records() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    let records: Arc<Mutex<Vec<PrintRecord>>> = Arc::default();
+    let captured = Arc::clone(&records);
+    pd.set_print_prefixes(&["synth"]);
+    pd.on_print_record(move |record| captured.lock().unwrap().push(record.clone()))
+        .unwrap();
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r to_print;
+    #X obj 20 50 print synth;
+    #X obj 120 50 print;
+    #X obj 220 20 r to_float;
+    #X obj 220 50 f;
+    #X connect 0 0 1 0;
+    #X connect 0 0 2 0;
+    #X connect 3 0 4 0;
+        "#,
+    )
+    .unwrap();
+
+    pd.send_double_to("to_print", 440.0).unwrap();
+    // [f] has no method for this selector, pd prints an error.
+    pd.send_message_to("to_float", "nonsense", &[]).unwrap();
+    ctx.receive_messages_from_pd();
+
+    let records = records.lock().unwrap();
+    let instance = pd.instance_number();
+    assert!(records.iter().all(|record| record.instance == instance));
+    assert!(records.contains(&PrintRecord {
+        instance,
+        level: PrintLevel::Post,
+        print_object: Some("synth".to_owned()),
+        text: "440".to_owned(),
+    }));
+    assert!(records.contains(&PrintRecord {
+        instance,
+        level: PrintLevel::Post,
+        print_object: Some("print".to_owned()),
+        text: "440".to_owned(),
+    }));
+    assert!(records
+        .iter()
+        .any(|record| record.level == PrintLevel::Error && record.text.contains("nonsense")));
+
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,67 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{error::ProcessError, functions::block_size, Pd};
+
+#[test]
+fn process_planar() {
+    let mut pd = Pd::init_and_configure(2, 2, 44100).unwrap();
+    // Swaps the channels.
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 adc~ 1 2;
+    #X obj 20 50 dac~ 2 1;
+    #X connect 0 0 1 0;
+    #X connect 0 1 1 1;
+        "#,
+    )
+    .unwrap();
+    pd.dsp_on().unwrap();
+
+    let mut ctx = pd.audio_context();
+    let frames = block_size() as usize * 4;
+    ctx.reserve_planar(frames);
+
+    let left: Vec<f32> = (0..frames).map(|frame| frame as f32).collect();
+    let right: Vec<f32> = (0..frames).map(|frame| -(frame as f32)).collect();
+    let (mut out_left, mut out_right) = (vec![0.0_f32; frames], vec![0.0_f32; frames]);
+    ctx.process_planar_float(&[&left, &right], &mut [&mut out_left, &mut out_right])
+        .unwrap();
+    assert_eq!(out_left, right);
+    assert_eq!(out_right, left);
+
+    let left: Vec<f64> = left.iter().map(|sample| f64::from(*sample)).collect();
+    let right: Vec<f64> = right.iter().map(|sample| f64::from(*sample)).collect();
+    let (mut out_left, mut out_right) = (vec![0.0_f64; frames], vec![0.0_f64; frames]);
+    ctx.process_planar_double(&[&left, &right], &mut [&mut out_left, &mut out_right])
+        .unwrap();
+    assert_eq!(out_left, right);
+    assert_eq!(out_right, left);
+
+    // Invalid buffers are reported instead of being processed.
+    assert!(matches!(
+        ctx.process_planar_double(&[&left], &mut [&mut out_left, &mut out_right]),
+        Err(ProcessError::InputChannelMismatch {
+            expected: 2,
+            found: 1
+        })
+    ));
+    assert!(matches!(
+        ctx.process_planar_double(&[&left, &right], &mut [&mut out_left]),
+        Err(ProcessError::OutputChannelMismatch {
+            expected: 2,
+            found: 1
+        })
+    ));
+    assert!(matches!(
+        ctx.process_planar_double(&[&left, &right[1..]], &mut [&mut out_left, &mut out_right]),
+        Err(ProcessError::UnevenChannelLengths)
+    ));
+    assert!(matches!(
+        ctx.process_planar_double(
+            &[&left[1..], &right[1..]],
+            &mut [&mut out_left[1..], &mut out_right[1..]]
+        ),
+        Err(ProcessError::PartialBlock { .. })
+    ));
+}
@@ -0,0 +1,89 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::ObjectError,
+    object::{Outlets, PdObject},
+    types::PdMessage,
+    Atom, Pd,
+};
+
+/// Sums the floats it receives and outputs the sum.
+struct Accumulate {
+    sum: f64,
+}
+
+impl PdObject for Accumulate {
+    const OUTLETS: usize = 1;
+
+    fn new(args: &[Atom]) -> Self {
+        let sum = match args.first() {
+            Some(Atom::Float(value)) => *value,
+            _ => 0.0,
+        };
+        Self { sum }
+    }
+
+    fn on_bang(&mut self, outlets: &Outlets) {
+        outlets.float(0, self.sum).unwrap();
+    }
+
+    fn on_float(&mut self, value: f64, outlets: &Outlets) {
+        self.sum += value;
+        self.on_bang(outlets);
+    }
+
+    fn on_message(&mut self, selector: &str, _args: &[Atom], _outlets: &Outlets) {
+        if selector == "reset" {
+            self.sum = 0.0;
+        }
+    }
+}
+
+#[test]
+fn rust_objects() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.register_class::<Accumulate>("accumulate").unwrap();
+    assert!(matches!(
+        pd.register_class::<Accumulate>("accumulate"),
+        Err(ObjectError::AlreadyRegistered(name)) if name == "accumulate"
+    ));
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r acc_in;
+    #X obj 20 50 accumulate 10;
+    #X obj 20 80 s acc_out;
+    #X connect 0 0 1 0;
+    #X connect 1 0 2 0;
+        "#,
+    )
+    .unwrap();
+
+    let messages = pd.message_receiver();
+    pd.subscribe_to("acc_out").unwrap();
+
+    pd.send_bang_to("acc_in").unwrap();
+    pd.send_double_to("acc_in", 5.0).unwrap();
+    pd.send_double_to("acc_in", -2.5).unwrap();
+    pd.send_message_to("acc_in", "reset", &[]).unwrap();
+    pd.send_double_to("acc_in", 1.0).unwrap();
+    ctx.receive_messages_from_pd();
+
+    let received: Vec<PdMessage> = messages.try_iter().collect();
+    assert_eq!(
+        received,
+        [10.0, 15.0, 12.5, 1.0]
+            .into_iter()
+            .map(|value| PdMessage::Double {
+                source: "acc_out".to_owned(),
+                value,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    pd.unsubscribe_from("acc_out");
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,89 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{functions::block_size, object::PdSignalObject, Atom, Pd};
+
+/// Sums its inputs with a gain to the left outlet, outputs the block size to the right outlet.
+struct Mix {
+    gain: f32,
+    block_size: usize,
+}
+
+impl PdSignalObject for Mix {
+    const INLETS: usize = 2;
+    const OUTLETS: usize = 2;
+
+    fn new(args: &[Atom]) -> Self {
+        let gain = match args.first() {
+            Some(Atom::Float(value)) => *value as f32,
+            _ => 1.0,
+        };
+        Self {
+            gain,
+            block_size: 0,
+        }
+    }
+
+    fn prepare(&mut self, sample_rate: f64, block_size: usize) {
+        assert_eq!(sample_rate, 44100.0);
+        self.block_size = block_size;
+    }
+
+    fn process(&mut self, inputs: &[&[f32]], outputs: &mut [&mut [f32]]) {
+        let (mixed, sizes) = outputs.split_at_mut(1);
+        for ((output, left), right) in mixed[0].iter_mut().zip(inputs[0]).zip(inputs[1]) {
+            *output = (left + right) * self.gain;
+        }
+        sizes[0].fill(self.block_size as f32);
+    }
+
+    fn on_message(&mut self, selector: &str, args: &[Atom]) {
+        if let ("gain", [Atom::Float(value)]) = (selector, args) {
+            self.gain = *value as f32;
+        }
+    }
+}
+
+#[test]
+fn rust_signal_objects() {
+    let output_channels = 2;
+    let mut pd = Pd::init_and_configure(0, output_channels, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.register_signal_class::<Mix>("mix~").unwrap();
+    assert!(pd.register_signal_class::<Mix>("mix~").is_err());
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 sig~ 1;
+    #X obj 120 20 sig~ 2;
+    #X obj 20 60 mix~ 0.5;
+    #X obj 20 100 dac~;
+    #X obj 220 20 r mix_gain;
+    #X connect 0 0 2 0;
+    #X connect 1 0 2 1;
+    #X connect 2 0 3 0;
+    #X connect 2 1 3 1;
+    #X connect 4 0 2 0;
+        "#,
+    )
+    .unwrap();
+    pd.dsp_on().unwrap();
+
+    let block = block_size() as usize;
+    let mut output = vec![0.0_f32; block * output_channels as usize];
+    ctx.process_float(1, &[], &mut output);
+    for frame in output.chunks_exact(output_channels as usize) {
+        assert_eq!(frame, [1.5, block as f32]);
+    }
+
+    pd.send_message_to("mix_gain", "gain", &[Atom::Float(2.0)])
+        .unwrap();
+    ctx.process_float(1, &[], &mut output);
+    for frame in output.chunks_exact(output_channels as usize) {
+        assert_eq!(frame, [6.0, block as f32]);
+    }
+
+    pd.dsp_off().unwrap();
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,84 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::{PdError, ProcessError},
+    functions::block_size,
+    schedule::{ScheduledMessage, Timestamp},
+    Pd,
+};
+
+#[test]
+fn scheduled_messages() {
+    let output_channels = 2;
+    let mut pd = Pd::init_and_configure(0, output_channels, 44100).unwrap();
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r scheduled;
+    #X obj 20 50 sig~;
+    #X obj 20 80 dac~;
+    #X connect 0 0 1 0;
+    #X connect 1 0 2 0;
+        "#,
+    )
+    .unwrap();
+    pd.dsp_on().unwrap();
+
+    let (sender, mut scheduler) = pd.scheduler();
+    let float = |value| ScheduledMessage::Float {
+        receiver: "scheduled".to_owned(),
+        value,
+    };
+    let block = block_size() as usize;
+
+    // Scheduled out of order, within the third tick and right at the start of the sixth.
+    sender
+        .send(Timestamp::Samples(5 * block as u64), float(2.0))
+        .unwrap();
+    sender
+        .send(Timestamp::Samples(2 * block as u64 + 10), float(1.0))
+        .unwrap();
+
+    let mut output = vec![0.0_f32; 8 * block * output_channels as usize];
+    scheduler.process_float(&[], &mut output).unwrap();
+    assert_eq!(scheduler.position(), 8 * block as u64);
+    assert_eq!(sender.position(), 8 * block as u64);
+
+    let left: Vec<f32> = output
+        .chunks_exact(output_channels as usize)
+        .map(|frame| frame[0])
+        .collect();
+    for (frame, sample) in left.iter().enumerate() {
+        let expected = match frame / block {
+            0 | 1 => 0.0,
+            2..=4 => 1.0,
+            _ => 2.0,
+        };
+        assert_eq!(*sample, expected, "frame {frame}");
+    }
+
+    // Relative to the current position of the clock.
+    sender.send_in(block as u64, float(3.0)).unwrap();
+    scheduler.process_float(&[], &mut output).unwrap();
+    assert_eq!(output[0], 2.0);
+    assert_eq!(output[block * output_channels as usize], 3.0);
+
+    // Buffers which don't match the ticks and the channels are refused before anything is processed.
+    let position = scheduler.position();
+    assert!(matches!(
+        scheduler.process_float(&[], &mut output[..block]),
+        Err(PdError::ProcessError(
+            ProcessError::OutputLengthMismatch { .. }
+        ))
+    ));
+    assert!(matches!(
+        scheduler.process_float(&[0.0; 4], &mut output),
+        Err(PdError::ProcessError(
+            ProcessError::InputLengthMismatch { .. }
+        ))
+    ));
+    assert_eq!(scheduler.position(), position);
+
+    drop(scheduler);
+    assert!(sender.send_in(0, float(0.0)).is_err());
+}
@@ -0,0 +1,49 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    functions::block_size,
+    schedule::{ScheduledMessage, Timestamp},
+    Pd,
+};
+
+const PATCH: &str = r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r scheduled;
+    #X obj 20 50 sig~;
+    #X obj 20 80 dac~;
+    #X connect 0 0 1 0;
+    #X connect 1 0 2 0;
+"#;
+
+#[test]
+fn scheduled_messages_instances() {
+    let output_channels = 2;
+    let mut first = Pd::init_and_configure(0, output_channels, 44100).unwrap();
+    let mut second = Pd::init_and_configure(0, output_channels, 44100).unwrap();
+    for pd in [&mut first, &mut second] {
+        pd.eval_patch(PATCH).unwrap();
+        pd.dsp_on().unwrap();
+    }
+
+    let (first_sender, mut first_scheduler) = first.scheduler();
+    let (_second_sender, mut second_scheduler) = second.scheduler();
+    let block = block_size() as usize;
+    let mut output = vec![0.0_f32; 2 * block * output_channels as usize];
+
+    // Due in the first block of the first instance, while the second one is the current instance.
+    first_sender
+        .send(
+            Timestamp::Samples(0),
+            ScheduledMessage::Float {
+                receiver: "scheduled".to_owned(),
+                value: 1.0,
+            },
+        )
+        .unwrap();
+    second_scheduler.process_float(&[], &mut output).unwrap();
+    first_scheduler.process_float(&[], &mut output).unwrap();
+    assert!(output.iter().all(|sample| *sample == 1.0));
+
+    second_scheduler.process_float(&[], &mut output).unwrap();
+    assert!(output.iter().all(|sample| *sample == 0.0));
+}
@@ -0,0 +1,128 @@
+#![allow(clippy::restriction)]
+
+use std::sync::{mpsc, Arc, Mutex};
+
+use libpd_rs::{functions::block_size, midi::MidiMessage, Pd};
+
+#[test]
+fn send_and_receive_midi_message() {
+    let sample_rate = 44100;
+    let output_channels = 2;
+
+    let midi_messages_received: Arc<Mutex<Vec<MidiMessage>>> = Arc::new(Mutex::new(vec![]));
+
+    let mut pd = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.open_patch("tests/patches/echo.pd").unwrap();
+
+    let messages_to_fill = midi_messages_received.clone();
+    pd.on_midi(move |message| {
+        messages_to_fill.lock().unwrap().push(message);
+    });
+
+    pd.dsp_on().unwrap();
+
+    let (tx, rx) = mpsc::channel::<()>();
+
+    let handle = std::thread::spawn(move || {
+        // Mimic audio callback buffers.
+        let input_buffer = [0.0f32; 512];
+        let mut output_buffer = [0.0f32; 1024];
+
+        // Run pd
+        loop {
+            // Mimic an audio callback.
+            let approximate_buffer_duration =
+                (output_buffer.len() as f32 / sample_rate as f32) * 1000.0;
+            std::thread::sleep(std::time::Duration::from_millis(
+                approximate_buffer_duration as u64,
+            ));
+
+            ctx.receive_midi_messages_from_pd();
+            let ticks = output_buffer.len() as i32 / (block_size() * output_channels);
+            ctx.process_float(ticks, &input_buffer, &mut output_buffer);
+            match rx.try_recv() {
+                Ok(_) => break,
+                _ => continue,
+            }
+        }
+    });
+
+    pd.send_midi(MidiMessage::NoteOn {
+        channel: 0,
+        pitch: 60,
+        velocity: 100,
+    })
+    .unwrap();
+    pd.send_midi(MidiMessage::NoteOff {
+        channel: 0,
+        pitch: 60,
+        velocity: 64,
+    })
+    .unwrap();
+    pd.send_midi(MidiMessage::ControlChange {
+        channel: 3,
+        controller: 7,
+        value: 127,
+    })
+    .unwrap();
+    pd.send_midi(MidiMessage::ProgramChange {
+        channel: 2,
+        program: 5,
+    })
+    .unwrap();
+    pd.send_midi(MidiMessage::PolyAfterTouch {
+        channel: 1,
+        pitch: 64,
+        value: 12,
+    })
+    .unwrap();
+
+    // Invalid messages are rejected before reaching pd.
+    assert!(pd
+        .send_midi(MidiMessage::NoteOn {
+            channel: 16,
+            pitch: 60,
+            velocity: 100,
+        })
+        .is_err());
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    // Stop pd.
+    tx.send(()).unwrap();
+    handle.join().unwrap();
+
+    assert_eq!(
+        *midi_messages_received.lock().unwrap(),
+        vec![
+            MidiMessage::NoteOn {
+                channel: 0,
+                pitch: 60,
+                velocity: 100,
+            },
+            MidiMessage::NoteOn {
+                channel: 0,
+                pitch: 60,
+                velocity: 0,
+            },
+            MidiMessage::ControlChange {
+                channel: 3,
+                controller: 7,
+                value: 127,
+            },
+            MidiMessage::ProgramChange {
+                channel: 2,
+                program: 5,
+            },
+            MidiMessage::PolyAfterTouch {
+                channel: 1,
+                pitch: 64,
+                value: 12,
+            },
+        ]
+    );
+
+    pd.close_patch().unwrap();
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "serde")]
+#![allow(clippy::restriction)]
+
+use std::sync::{Arc, Mutex};
+
+use libpd_rs::{atom::from_atoms, types::AddressedMessage, Atom, Pd};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Voice {
+    Note { pitch: u8, velocity: f32 },
+    Off,
+}
+
+#[test]
+fn serde_messages() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.eval_patch(
+        r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r to_pd;
+    #X obj 20 50 s from_pd;
+    #X connect 0 0 1 0;
+        "#,
+    )
+    .unwrap();
+
+    let voices: Arc<Mutex<Vec<Voice>>> = Arc::default();
+    let received = Arc::clone(&voices);
+    pd.on_list(move |_source, list| received.lock().unwrap().push(from_atoms(list).unwrap()))
+        .unwrap();
+    let _receiver = pd.start_listening_from("from_pd").unwrap();
+
+    // Messages are stored as JSON and sent to pd later.
+    let json = r#"[
+        {"receiver": "to_pd", "selector": "list", "atoms": ["note", 60, 0.5]},
+        {"receiver": "to_pd", "selector": "list", "atoms": ["off"]}
+    ]"#;
+    let messages: Vec<AddressedMessage> = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        messages[0].atoms,
+        vec![Atom::from("note"), Atom::Float(60.0), Atom::Float(0.5)]
+    );
+    for message in &messages {
+        pd.send_message_to(&message.receiver, &message.selector, &message.atoms)
+            .unwrap();
+    }
+    ctx.receive_messages_from_pd();
+
+    assert_eq!(
+        *voices.lock().unwrap(),
+        vec![
+            Voice::Note {
+                pitch: 60,
+                velocity: 0.5
+            },
+            Voice::Off
+        ]
+    );
+
+    let round_trip: Vec<AddressedMessage> =
+        serde_json::from_str(&serde_json::to_string(&messages).unwrap()).unwrap();
+    assert_eq!(round_trip, messages);
+}
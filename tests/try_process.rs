@@ -0,0 +1,51 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{error::ProcessError, functions::block_size, Pd};
+
+#[test]
+fn try_process() {
+    let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+    let block = block_size() as usize;
+
+    let input = vec![0.0_f32; block * 4];
+    let mut output = vec![0.0_f32; block * 8];
+    ctx.try_process_float(4, &input, &mut output).unwrap();
+
+    assert!(matches!(
+        ctx.try_process_float(4, &[], &mut output),
+        Err(ProcessError::InputLengthMismatch { expected, found: 0 }) if expected == block * 4
+    ));
+    assert!(matches!(
+        ctx.try_process_float(5, &input, &mut output),
+        Err(ProcessError::InputLengthMismatch { .. })
+    ));
+    assert!(matches!(
+        ctx.try_process_float(4, &input, &mut output[1..]),
+        Err(ProcessError::OutputLengthMismatch { expected, .. }) if expected == block * 8
+    ));
+    assert!(matches!(
+        ctx.try_process_float(-1, &input, &mut output),
+        Err(ProcessError::NegativeTicks(-1))
+    ));
+
+    let input = vec![0.0_f64; block * 2];
+    let mut output = vec![0.0_f64; block * 4];
+    ctx.try_process_double(2, &input, &mut output).unwrap();
+    let input = vec![0_i16; block];
+    let mut output = vec![0_i16; block * 2];
+    ctx.try_process_short(1, &input, &mut output).unwrap();
+
+    // Raw processing is a single tick.
+    ctx.try_process_raw_short(&input, &mut output).unwrap();
+    let input = vec![0.0_f32; block];
+    let mut output = vec![0.0_f32; block * 2];
+    ctx.try_process_raw(&input, &mut output).unwrap();
+    assert!(matches!(
+        ctx.try_process_raw(&input, &mut output[..block]),
+        Err(ProcessError::OutputLengthMismatch { .. })
+    ));
+    let input = vec![0.0_f64; block];
+    let mut output = vec![0.0_f64; block * 2];
+    ctx.try_process_raw_double(&input, &mut output).unwrap();
+}